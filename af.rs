@@ -0,0 +1,263 @@
+/* Alternate Function Mapping */
+/* Datasheet Table: Alternate Function */
+
+use super::common;
+use super::gpio::{AltFunc, Port};
+
+// Each pin can carry up to 16 alternate functions selected through GPIOx_AFRL / GPIOx_AFRH.
+// The signal a pin carries on a given AF number depends on the chip, the table below holds the
+// valid (pin, signal) pairs and the AF number that selects them, taken from the datasheet alternate function tables.
+// A pin listed for a chip might not be bonded out on every package of that chip.
+
+/* Enumerations */
+/* Supported Chip Variants */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Chip {
+    Stm32L43x,                  // STM32L431 / L432 / L433 / L442 / L443
+    Stm32L47x                   // STM32L475 / L476 / L486
+}
+
+/* Peripheral Signals */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Signal {
+    Usart1Tx,
+    Usart1Rx,
+    Usart2Tx,
+    Usart2Rx,
+    Usart3Tx,
+    Usart3Rx,
+    Lpuart1Tx,
+    Lpuart1Rx,
+    I2c1Scl,
+    I2c1Sda,
+    I2c2Scl,
+    I2c2Sda,
+    I2c3Scl,
+    I2c3Sda,
+    Spi1Sck,
+    Spi1Miso,
+    Spi1Mosi,
+    Spi2Sck,
+    Spi2Miso,
+    Spi2Mosi,
+    Spi3Sck,
+    Spi3Miso,
+    Spi3Mosi,
+    Can1Rx,
+    Can1Tx,
+    Tim1Ch1,
+    Tim1Ch2,
+    Tim1Ch3,
+    Tim1Ch4,
+    Tim1Ch1n,
+    Tim1Ch2n,
+    Tim1Ch3n,
+    Tim1Bkin,
+    Tim1Bkin2,
+    Tim1Etr,
+    Tim2Ch1,
+    Tim2Ch2,
+    Tim2Ch3,
+    Tim2Ch4,
+    Tim3Ch1,
+    Tim3Ch2,
+    Tim3Ch3,
+    Tim3Ch4,
+    Tim15Ch1,
+    Tim15Ch2,
+    Tim15Ch1n,
+    Tim16Ch1,
+    Tim16Ch1n
+}
+
+/* Table Entry, Port, Pin, Signal, Alternate Function, Chips The Mapping Is Valid For */
+struct Map {
+    port:       Port,
+    pin:        u32,
+    signal:     Signal,
+    af:         AltFunc,
+    chips:      u32
+}
+
+/* Chip Bits */
+const L43X:             u32 = common::BIT_0;
+const L47X:             u32 = common::BIT_1;
+const ALL:              u32 = L43X | L47X;
+
+const fn map(port: Port, pin: u32, signal: Signal, af: AltFunc, chips: u32) -> Map {
+    return Map { port, pin, signal, af, chips };
+}
+
+static MAP: &[Map] = &[
+    /* USART1 */
+    map(Port::PortA,  9, Signal::Usart1Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortA, 10, Signal::Usart1Rx,    AltFunc::Af7,   ALL),
+    map(Port::PortB,  6, Signal::Usart1Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortB,  7, Signal::Usart1Rx,    AltFunc::Af7,   ALL),
+    /* USART2 */
+    map(Port::PortA,  2, Signal::Usart2Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortA,  3, Signal::Usart2Rx,    AltFunc::Af7,   ALL),
+    map(Port::PortA, 15, Signal::Usart2Rx,    AltFunc::Af3,   ALL),
+    map(Port::PortD,  5, Signal::Usart2Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortD,  6, Signal::Usart2Rx,    AltFunc::Af7,   ALL),
+    /* USART3 */
+    map(Port::PortB, 10, Signal::Usart3Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortB, 11, Signal::Usart3Rx,    AltFunc::Af7,   ALL),
+    map(Port::PortC,  4, Signal::Usart3Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortC,  5, Signal::Usart3Rx,    AltFunc::Af7,   ALL),
+    map(Port::PortC, 10, Signal::Usart3Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortC, 11, Signal::Usart3Rx,    AltFunc::Af7,   ALL),
+    map(Port::PortD,  8, Signal::Usart3Tx,    AltFunc::Af7,   ALL),
+    map(Port::PortD,  9, Signal::Usart3Rx,    AltFunc::Af7,   ALL),
+    /* LPUART1 */
+    map(Port::PortA,  2, Signal::Lpuart1Tx,   AltFunc::Af8,   ALL),
+    map(Port::PortA,  3, Signal::Lpuart1Rx,   AltFunc::Af8,   ALL),
+    map(Port::PortB, 10, Signal::Lpuart1Rx,   AltFunc::Af8,   ALL),
+    map(Port::PortB, 11, Signal::Lpuart1Tx,   AltFunc::Af8,   ALL),
+    map(Port::PortC,  0, Signal::Lpuart1Rx,   AltFunc::Af8,   ALL),
+    map(Port::PortC,  1, Signal::Lpuart1Tx,   AltFunc::Af8,   ALL),
+    /* I2C1 */
+    map(Port::PortA,  9, Signal::I2c1Scl,     AltFunc::Af4,   L43X),
+    map(Port::PortA, 10, Signal::I2c1Sda,     AltFunc::Af4,   L43X),
+    map(Port::PortB,  6, Signal::I2c1Scl,     AltFunc::Af4,   ALL),
+    map(Port::PortB,  7, Signal::I2c1Sda,     AltFunc::Af4,   ALL),
+    map(Port::PortB,  8, Signal::I2c1Scl,     AltFunc::Af4,   ALL),
+    map(Port::PortB,  9, Signal::I2c1Sda,     AltFunc::Af4,   ALL),
+    /* I2C2 */
+    map(Port::PortB, 10, Signal::I2c2Scl,     AltFunc::Af4,   ALL),
+    map(Port::PortB, 11, Signal::I2c2Sda,     AltFunc::Af4,   ALL),
+    map(Port::PortB, 13, Signal::I2c2Scl,     AltFunc::Af4,   ALL),
+    map(Port::PortB, 14, Signal::I2c2Sda,     AltFunc::Af4,   ALL),
+    /* I2C3 */
+    map(Port::PortA,  7, Signal::I2c3Scl,     AltFunc::Af4,   L43X),
+    map(Port::PortB,  4, Signal::I2c3Sda,     AltFunc::Af4,   L43X),
+    map(Port::PortC,  0, Signal::I2c3Scl,     AltFunc::Af4,   ALL),
+    map(Port::PortC,  1, Signal::I2c3Sda,     AltFunc::Af4,   ALL),
+    /* SPI1 */
+    map(Port::PortA,  1, Signal::Spi1Sck,     AltFunc::Af5,   L43X),
+    map(Port::PortA,  5, Signal::Spi1Sck,     AltFunc::Af5,   ALL),
+    map(Port::PortA,  6, Signal::Spi1Miso,    AltFunc::Af5,   ALL),
+    map(Port::PortA,  7, Signal::Spi1Mosi,    AltFunc::Af5,   ALL),
+    map(Port::PortA, 11, Signal::Spi1Miso,    AltFunc::Af5,   L43X),
+    map(Port::PortA, 12, Signal::Spi1Mosi,    AltFunc::Af5,   L43X),
+    map(Port::PortB,  3, Signal::Spi1Sck,     AltFunc::Af5,   ALL),
+    map(Port::PortB,  4, Signal::Spi1Miso,    AltFunc::Af5,   ALL),
+    map(Port::PortB,  5, Signal::Spi1Mosi,    AltFunc::Af5,   ALL),
+    /* SPI2 */
+    map(Port::PortB, 10, Signal::Spi2Sck,     AltFunc::Af5,   ALL),
+    map(Port::PortB, 13, Signal::Spi2Sck,     AltFunc::Af5,   ALL),
+    map(Port::PortB, 14, Signal::Spi2Miso,    AltFunc::Af5,   ALL),
+    map(Port::PortB, 15, Signal::Spi2Mosi,    AltFunc::Af5,   ALL),
+    map(Port::PortC,  2, Signal::Spi2Miso,    AltFunc::Af5,   ALL),
+    map(Port::PortC,  3, Signal::Spi2Mosi,    AltFunc::Af5,   ALL),
+    /* SPI3 */
+    map(Port::PortB,  3, Signal::Spi3Sck,     AltFunc::Af6,   ALL),
+    map(Port::PortB,  4, Signal::Spi3Miso,    AltFunc::Af6,   ALL),
+    map(Port::PortB,  5, Signal::Spi3Mosi,    AltFunc::Af6,   ALL),
+    map(Port::PortC, 10, Signal::Spi3Sck,     AltFunc::Af6,   ALL),
+    map(Port::PortC, 11, Signal::Spi3Miso,    AltFunc::Af6,   ALL),
+    map(Port::PortC, 12, Signal::Spi3Mosi,    AltFunc::Af6,   ALL),
+    /* CAN1 */
+    map(Port::PortA, 11, Signal::Can1Rx,      AltFunc::Af9,   ALL),
+    map(Port::PortA, 12, Signal::Can1Tx,      AltFunc::Af9,   ALL),
+    map(Port::PortB,  8, Signal::Can1Rx,      AltFunc::Af9,   ALL),
+    map(Port::PortB,  9, Signal::Can1Tx,      AltFunc::Af9,   ALL),
+    /* TIM1 */
+    map(Port::PortA,  6, Signal::Tim1Bkin,    AltFunc::Af1,   ALL),
+    map(Port::PortA,  7, Signal::Tim1Ch1n,    AltFunc::Af1,   ALL),
+    map(Port::PortA,  8, Signal::Tim1Ch1,     AltFunc::Af1,   ALL),
+    map(Port::PortA,  9, Signal::Tim1Ch2,     AltFunc::Af1,   ALL),
+    map(Port::PortA, 10, Signal::Tim1Ch3,     AltFunc::Af1,   ALL),
+    map(Port::PortA, 11, Signal::Tim1Ch4,     AltFunc::Af1,   ALL),
+    map(Port::PortA, 11, Signal::Tim1Bkin2,   AltFunc::Af2,   ALL),
+    map(Port::PortA, 12, Signal::Tim1Etr,     AltFunc::Af1,   ALL),
+    map(Port::PortB,  0, Signal::Tim1Ch2n,    AltFunc::Af1,   ALL),
+    map(Port::PortB,  1, Signal::Tim1Ch3n,    AltFunc::Af1,   ALL),
+    map(Port::PortB, 12, Signal::Tim1Bkin,    AltFunc::Af1,   ALL),
+    map(Port::PortB, 13, Signal::Tim1Ch1n,    AltFunc::Af1,   ALL),
+    map(Port::PortB, 14, Signal::Tim1Ch2n,    AltFunc::Af1,   ALL),
+    map(Port::PortB, 15, Signal::Tim1Ch3n,    AltFunc::Af1,   ALL),
+    /* TIM2 */
+    map(Port::PortA,  0, Signal::Tim2Ch1,     AltFunc::Af1,   ALL),
+    map(Port::PortA,  1, Signal::Tim2Ch2,     AltFunc::Af1,   ALL),
+    map(Port::PortA,  2, Signal::Tim2Ch3,     AltFunc::Af1,   ALL),
+    map(Port::PortA,  3, Signal::Tim2Ch4,     AltFunc::Af1,   ALL),
+    map(Port::PortA,  5, Signal::Tim2Ch1,     AltFunc::Af1,   ALL),
+    map(Port::PortA, 15, Signal::Tim2Ch1,     AltFunc::Af1,   ALL),
+    map(Port::PortB,  3, Signal::Tim2Ch2,     AltFunc::Af1,   ALL),
+    map(Port::PortB, 10, Signal::Tim2Ch3,     AltFunc::Af1,   ALL),
+    map(Port::PortB, 11, Signal::Tim2Ch4,     AltFunc::Af1,   ALL),
+    /* TIM3 */
+    map(Port::PortA,  6, Signal::Tim3Ch1,     AltFunc::Af2,   L47X),
+    map(Port::PortA,  7, Signal::Tim3Ch2,     AltFunc::Af2,   L47X),
+    map(Port::PortB,  0, Signal::Tim3Ch3,     AltFunc::Af2,   L47X),
+    map(Port::PortB,  1, Signal::Tim3Ch4,     AltFunc::Af2,   L47X),
+    map(Port::PortB,  4, Signal::Tim3Ch1,     AltFunc::Af2,   L47X),
+    map(Port::PortB,  5, Signal::Tim3Ch2,     AltFunc::Af2,   L47X),
+    /* TIM15 */
+    map(Port::PortA,  1, Signal::Tim15Ch1n,   AltFunc::Af14,  ALL),
+    map(Port::PortA,  2, Signal::Tim15Ch1,    AltFunc::Af14,  ALL),
+    map(Port::PortA,  3, Signal::Tim15Ch2,    AltFunc::Af14,  ALL),
+    map(Port::PortB, 14, Signal::Tim15Ch1,    AltFunc::Af14,  ALL),
+    /* TIM16 */
+    map(Port::PortA,  6, Signal::Tim16Ch1,    AltFunc::Af14,  ALL),
+    map(Port::PortB,  6, Signal::Tim16Ch1n,   AltFunc::Af14,  ALL),
+    map(Port::PortB,  8, Signal::Tim16Ch1,    AltFunc::Af14,  ALL),
+];
+
+fn chip_bit(chip: Chip) -> u32 {
+    return match chip {
+        Chip::Stm32L43x => L43X,
+        Chip::Stm32L47x => L47X
+    };
+}
+
+/* Get The Alternate Function That Carries The Signal On The Pin, None If The Pin Can Not Carry It */
+pub fn lookup(chip: Chip, port: Port, pin: u32, signal: Signal) -> Option<AltFunc> {
+    let bit = chip_bit(chip);
+
+    for m in MAP.iter() {
+        if (m.chips & bit) != 0 && m.port == port && m.pin == pin && m.signal == signal {
+            return Some(m.af);
+        }
+    }
+    return None;
+}
+
+/* ADC Inputs, ADC12_IN1 - IN16 Are The Same Pins On Both Chips (ADC1 Only On STM32L43x) */
+static ADC_MAP: &[(Port, u32, u32)] = &[
+    (Port::PortC,  0,  1),
+    (Port::PortC,  1,  2),
+    (Port::PortC,  2,  3),
+    (Port::PortC,  3,  4),
+    (Port::PortA,  0,  5),
+    (Port::PortA,  1,  6),
+    (Port::PortA,  2,  7),
+    (Port::PortA,  3,  8),
+    (Port::PortA,  4,  9),
+    (Port::PortA,  5, 10),
+    (Port::PortA,  6, 11),
+    (Port::PortA,  7, 12),
+    (Port::PortC,  4, 13),
+    (Port::PortC,  5, 14),
+    (Port::PortB,  0, 15),
+    (Port::PortB,  1, 16),
+];
+
+/* Get The ADC Input Channel Of The Pin, None If The Pin Is Not An ADC Input */
+pub fn adc_channel(port: Port, pin: u32) -> Option<u32> {
+    for m in ADC_MAP.iter() {
+        if m.0 == port && m.1 == pin {
+            return Some(m.2);
+        }
+    }
+    return None;
+}
+
+/* The Analog Switch Control Register (GPIOx_ASCR) Only Exists On The STM32L47x/L48x */
+pub fn has_ascr(chip: Chip) -> bool {
+    return match chip {
+        Chip::Stm32L43x => false,
+        Chip::Stm32L47x => true
+    };
+}
@@ -0,0 +1,94 @@
+/* Timer DMA Burst Waveform Driver (Timer Update + DMA) */
+
+use super::{dma, timer};
+
+// On every update event the timer requests a burst, the DMA writes the next words of the buffer to DMAR
+// and the timer spreads them over the registers from the burst base, so each period loads the settings of the next one.
+// A pulse train is a buffer of ARR, RCR, CCR1 triples (burst base ARR, length 3), one triple per pulse:
+// • ARR sets the period of the pulse, CCR1 the high time in PWM mode 1.
+// • RCR is reserved on timers without a repetition counter, the word is still sent but ignored.
+// With ARR and CCR preload set the values written during a period only apply from the next update event,
+// so the first period runs with the registers set before the start.
+// Timer set up: PWM on channel 1 with preload, the DMA channel request must be the update event of the same timer.
+
+pub struct Burst<'a> {
+    timer:      &'a timer::Timer,   // Timer Receiving The Burst
+    dma:        &'a dma::Dma,       // DMA Controller
+    dma_ch:     u32,                // DMA Channel Connected To The Timer Update Request
+    len:        u32                 // Registers Written Per Update Event
+}
+
+/* Words Per Pulse Of A Pulse Train, ARR RCR CCR1 */
+pub const PULSE_WORDS:  usize = 3;
+
+const NDT_MAX:          usize = 0xFFFF;
+
+/* Fill The Buffer With A Pulse Train Whose Period Steps Linearly From first To last (ARR Values), High Time In Counts */
+/* Returns The Number Of Words Written, A Stepper Acceleration Ramp Is A Train From A Long To A Short Period */
+pub fn ramp(first: u32, last: u32, high: u32, buf: &mut [u32]) -> usize {
+    let steps = buf.len() / PULSE_WORDS;
+
+    for (i, pulse) in buf.chunks_exact_mut(PULSE_WORDS).enumerate() {
+        let arr = match steps {
+            1   =>  first,
+            _   =>  {
+                let span = (last as i64) - (first as i64);
+                ((first as i64) + ((span * i as i64) / (steps as i64 - 1))) as u32
+            }
+        };
+
+        pulse[0] = arr;
+        pulse[1] = 0;
+        pulse[2] = if high > arr { arr } else { high };
+    }
+    return steps * PULSE_WORDS;
+}
+
+impl<'a> Burst<'a> {
+    /* Initialize The Structure And Set Up The Burst And DMA Channel, None If The Burst Runs Past BDTR */
+    /* Circular Repeats The Buffer Until Stopped */
+    pub fn init(timer: &'a timer::Timer, base: timer::BurstReg, len: u32, dma: &'a dma::Dma, dma_ch: u32, dma_request: u32, circ: bool) -> Option<Burst<'a>> {
+        timer.clr_dma_update();
+
+        if !timer.set_burst(base, len) {
+            return None;
+        }
+
+        dma.open(dma_ch, dma_request, dma::Direction::MemToPeriph, dma::Size::Bits32, dma::Size::Bits32, dma::Priority::High);
+        dma.set_circular(dma_ch, circ);
+
+        return Some(Burst {
+            timer,
+            dma,
+            dma_ch,
+            len
+        });
+    }
+
+    /* Stream The Buffer Into The Timer And Start It, Returns False If The Buffer Is Not Whole Bursts Or Too Long */
+    /* The Buffer Must Not Change Until The Transfer Is Done */
+    pub fn start(&self, buf: &[u32]) -> bool {
+        if buf.is_empty() || buf.len() > NDT_MAX || !buf.len().is_multiple_of(self.len as usize) {
+            return false;
+        }
+
+        self.timer.stop();
+        self.timer.clr_cnt();
+        self.dma.start(self.dma_ch, self.timer.get_dmar_addr(), buf.as_ptr() as u32, buf.len() as u32);
+        self.timer.set_dma_update();
+        self.timer.start();
+        return true;
+    }
+
+    /* Get If The Transfer Is Still Running */
+    pub fn busy(&self) -> bool {
+        return !self.dma.get_tc(self.dma_ch) && self.dma.get_cnt(self.dma_ch) > 0;
+    }
+
+    /* Stop The Transfer And The Timer */
+    pub fn stop(&self) {
+        self.timer.clr_dma_update();
+        self.dma.stop(self.dma_ch);
+        self.timer.stop();
+    }
+}
@@ -118,3 +118,8 @@ pub fn wait_in_us(us: u32, rng: MsiRange) -> u32 {
 }
 
 pub const WAIT100US:    u32 = 1600;
+
+/* Blocking Microsecond Delay Source, Used By The Bit Banged Drivers */
+pub trait DelayUs {
+    fn delay_us(&self, us: u32);
+}
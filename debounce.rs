@@ -0,0 +1,245 @@
+/* Software Debounced Button Input */
+
+use super::gpio;
+
+// The pin is sampled once per tick (timer or SysTick interrupt, or a polled loop with a fixed period).
+// A change on the pin is only accepted once it has been stable for the debounce time,
+// all times are counted in ticks so the state machine is independent of the tick source.
+// Debounce holds only the stable state filter, it is shared by Button and the keypad scanner (one per key).
+// Events:
+// • Press: the debounced input went active.
+// • Release: the debounced input went inactive.
+// • LongPress: the input has been held active for the long press time, sent once per press.
+// • DoubleClick: the input went active again within the double click time of the last release,
+//   sent instead of the second Press. A release after a long press or a double click does not start a double click,
+//   so a third quick press is a plain Press.
+
+/* Enumerations */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Event {
+    Press,
+    Release,
+    LongPress,
+    DoubleClick
+}
+
+pub struct Button {
+    pin:            u32,        // Pin Mask On The Port
+    active_low:     bool,       // True If The Pin Reads Low When Pressed
+    input:          Debounce,   // Debounced State, True = Pressed
+    long_press:     u32,        // Ticks Held Before A Long Press, 0 = Disabled
+    double_click:   u32,        // Ticks After A Release A Press Counts As A Double Click, 0 = Disabled
+    held:           u32,        // Ticks The Debounced State Has Been Pressed
+    long_sent:      bool,       // Long Press Sent For This Press
+    gap:            u32,        // Ticks Since The Last Release
+    wait_double:    bool,       // Waiting On A Second Press For A Double Click
+    double_sent:    bool        // Double Click Sent For This Press
+}
+
+/* Stable State Filter */
+#[derive(Clone, Copy)]
+pub struct Debounce {
+    debounce:       u32,        // Samples The Input Must Be Stable Before A Change Is Accepted
+    state:          bool,       // Debounced State
+    cnt:            u32         // Samples The Raw Input Has Differed From The Debounced State
+}
+
+impl Button {
+    /* Initialize The Structure, All Times In Ticks */
+    pub fn init(pin: u32, active_low: bool, debounce: u32, long_press: u32, double_click: u32) -> Button {
+        return Button {
+            pin,
+            active_low,
+            input:          Debounce::init(debounce),
+            long_press,
+            double_click,
+            held:           0,
+            long_sent:      false,
+            gap:            0,
+            wait_double:    false,
+            double_sent:    false
+        };
+    }
+
+    /* Sample The Pin And Run The State Machine, Call Once Per Tick */
+    pub fn poll(&mut self, port: &gpio::Gpio) -> Option<Event> {
+        let pressed = port.get_pin(self.pin) != self.active_low;
+
+        return self.update(pressed);
+    }
+
+    /* Run The State Machine On A Raw Sample, True = Pressed, Call Once Per Tick */
+    pub fn update(&mut self, pressed: bool) -> Option<Event> {
+        if self.input.update(pressed).is_some() {
+            return self.edge();
+        }
+
+        if self.input.get_state() {
+            self.held = self.held.saturating_add(1);
+
+            if self.long_press > 0 && !self.long_sent && self.held >= self.long_press {
+                self.long_sent = true;
+                self.wait_double = false;
+                return Some(Event::LongPress);
+            }
+        } else if self.wait_double {
+            self.gap = self.gap.saturating_add(1);
+
+            if self.gap >= self.double_click {
+                self.wait_double = false;
+            }
+        }
+
+        return None;
+    }
+
+    /* Get The Debounced State, True = Pressed */
+    pub fn get_state(&self) -> bool {
+        return self.input.get_state();
+    }
+
+    /* Handle A Debounced Change Of State */
+    fn edge(&mut self) -> Option<Event> {
+        if self.input.get_state() {
+            self.held = 0;
+            self.long_sent = false;
+            self.double_sent = self.wait_double;
+
+            if self.wait_double {
+                self.wait_double = false;
+                return Some(Event::DoubleClick);
+            }
+            return Some(Event::Press);
+        } else {
+            self.gap = 0;
+            self.wait_double = self.double_click > 0 && !self.long_sent && !self.double_sent;
+
+            return Some(Event::Release);
+        }
+    }
+}
+
+impl Debounce {
+    /* Initialize The Structure, Released, Time In Samples */
+    pub const fn init(debounce: u32) -> Debounce {
+        return Debounce {
+            debounce,
+            state:          false,
+            cnt:            0
+        };
+    }
+
+    /* Filter A Raw Sample, Returns The New State When A Change Is Accepted */
+    pub fn update(&mut self, sample: bool) -> Option<bool> {
+        if sample == self.state {
+            self.cnt = 0;
+            return None;
+        }
+
+        self.cnt = self.cnt.saturating_add(1);
+
+        if self.cnt < self.debounce {
+            return None;
+        }
+
+        self.cnt = 0;
+        self.state = sample;
+        return Some(sample);
+    }
+
+    /* Get The Debounced State */
+    pub fn get_state(&self) -> bool {
+        return self.state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Button, Event};
+
+    const DEBOUNCE:     u32 = 3;
+    const LONG:         u32 = 50;
+    const DOUBLE:       u32 = 10;
+
+    /* Feed The Same Sample For A Number Of Ticks, Returns The Events In Order */
+    fn run(b: &mut Button, pressed: bool, ticks: u32) -> [Option<Event>; 2] {
+        let mut events = [None; 2];
+        let mut n = 0;
+
+        for _ in 0..ticks {
+            if let Some(event) = b.update(pressed) {
+                assert!(n < events.len(), "more events than expected");
+                events[n] = Some(event);
+                n += 1;
+            }
+        }
+        return events;
+    }
+
+    fn button() -> Button {
+        return Button::init(0, false, DEBOUNCE, LONG, DOUBLE);
+    }
+
+    #[test]
+    fn press_and_release() {
+        let mut b = button();
+
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+        assert!(b.get_state());
+        assert!(run(&mut b, false, DOUBLE + DEBOUNCE) == [Some(Event::Release), None]);
+        assert!(!b.get_state());
+    }
+
+    #[test]
+    fn bounce_is_rejected() {
+        let mut b = button();
+
+        for _ in 0..10 {
+            assert!(run(&mut b, true, DEBOUNCE - 1) == [None, None]);
+            assert!(run(&mut b, false, 1) == [None, None]);
+        }
+        assert!(!b.get_state());
+    }
+
+    #[test]
+    fn long_press_once() {
+        let mut b = button();
+
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+        assert!(run(&mut b, true, LONG * 3) == [Some(Event::LongPress), None]);
+        assert!(run(&mut b, false, DEBOUNCE) == [Some(Event::Release), None]);
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);    // No double click after a long press
+    }
+
+    #[test]
+    fn double_click() {
+        let mut b = button();
+
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+        assert!(run(&mut b, false, DEBOUNCE) == [Some(Event::Release), None]);
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::DoubleClick), None]);
+        assert!(run(&mut b, false, DEBOUNCE) == [Some(Event::Release), None]);
+    }
+
+    #[test]
+    fn double_click_timeout() {
+        let mut b = button();
+
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+        assert!(run(&mut b, false, DEBOUNCE + DOUBLE) == [Some(Event::Release), None]);
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+    }
+
+    #[test]
+    fn third_quick_press_is_a_press() {
+        let mut b = button();
+
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+        assert!(run(&mut b, false, DEBOUNCE) == [Some(Event::Release), None]);
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::DoubleClick), None]);
+        assert!(run(&mut b, false, DEBOUNCE) == [Some(Event::Release), None]);
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::Press), None]);
+        assert!(run(&mut b, false, DEBOUNCE) == [Some(Event::Release), None]);
+        assert!(run(&mut b, true, DEBOUNCE) == [Some(Event::DoubleClick), None]);
+    }
+}
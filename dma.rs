@@ -0,0 +1,248 @@
+/* Direct Memory Access Controller (DMA) */
+
+use super::{common, pointer};
+
+// Each controller has 7 channels, a channel is connected to a peripheral request through DMA_CSELR.
+// Channel configuration procedure:
+// 1. Set the peripheral register address in DMA_CPARx, the data is moved from/to this address after the peripheral event.
+// 2. Set the memory address in DMA_CMARx, the data is written to or read from this memory after the peripheral event.
+// 3. Configure the total number of data to transfer in DMA_CNDTRx, after each data transfer this value is decremented.
+// 4. Configure the channel priority, direction, circular mode, increment mode and data sizes in DMA_CCRx.
+// 5. Activate the channel by setting the EN bit in DMA_CCRx.
+// The channel registers can only be written while EN = 0.
+
+pub struct Dma {
+    base:       u32,            // Base - Used For Channel Register Creation
+    isr:        *mut u32,       // Interrupt Status Register
+    ifcr:       *mut u32,       // Interrupt Flag Clear Register
+    cselr:      *mut u32,       // Channel Selection Register
+}
+
+/* Register Offset */
+const ISR:      u32 = 0x00;
+const IFCR:     u32 = 0x04;
+const CSELR:    u32 = 0xA8;
+
+/* Channel Register Offset, Channel x Is At CH_BASE + CH_SIZE * (x - 1) */
+const CCR:      u32 = 0x08;
+const CNDTR:    u32 = 0x0C;
+const CPAR:     u32 = 0x10;
+const CMAR:     u32 = 0x14;
+const CH_SIZE:  u32 = 0x14;
+
+/* Enumerations */
+// 0: Read from peripheral     1: Read from memory
+pub enum Direction {
+    PeriphToMem,
+    MemToPeriph
+}
+
+// 00: 8-bits     01: 16-bits     10: 32-bits
+pub enum Size {
+    Bits8,
+    Bits16,
+    Bits32
+}
+
+// 00: Low     01: Medium     10: High     11: Very high
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+    VeryHigh
+}
+
+/* Register Masks */
+/* CCR */
+const SIZE_MASK:        u32 = common::MASK_2_BIT;
+const PL_MASK:          u32 = common::MASK_2_BIT;
+/* CNDTR */
+const NDT_MASK:         u32 = common::MASK_16_BIT;
+/* CSELR */
+const CS_MASK:          u32 = common::MASK_4_BIT;
+/* ISR */
+const FLAG_MASK:        u32 = common::MASK_4_BIT;
+
+/* Register Bits */
+/* CCR */
+const EN_BIT:           u32 = common::BIT_0;            /* Channel Enable */
+const TCIE_BIT:         u32 = common::BIT_1;            /* Transfer Complete Interrupt Enable */
+const HTIE_BIT:         u32 = common::BIT_2;            /* Half Transfer Interrupt Enable */
+const TEIE_BIT:         u32 = common::BIT_3;            /* Transfer Error Interrupt Enable */
+const DIR_BIT:          u32 = common::BIT_4;            /* 0 = Read From Peripheral, 1 = Read From Memory */
+const CIRC_BIT:         u32 = common::BIT_5;            /* Circular Mode */
+const PINC_BIT:         u32 = common::BIT_6;            /* Peripheral Increment Mode */
+const MINC_BIT:         u32 = common::BIT_7;            /* Memory Increment Mode */
+
+/* ISR, Shifted By FLAG_OFFSET * (x - 1) */
+const TCIF_BIT:         u32 = common::BIT_1;            /* Transfer Complete Flag */
+const HTIF_BIT:         u32 = common::BIT_2;            /* Half Transfer Flag */
+const TEIF_BIT:         u32 = common::BIT_3;            /* Transfer Error Flag */
+
+/* Register Offsets */
+/* CCR */
+const PSIZE_OFFSET:     u32 = 8;
+const MSIZE_OFFSET:     u32 = 10;
+const PL_OFFSET:        u32 = 12;
+/* CSELR */
+const CS_OFFSET:        u32 = 4;
+/* ISR */
+const FLAG_OFFSET:      u32 = 4;
+
+const CHANNELS:         u32 = 7;
+
+impl Dma {
+    /* Initialize The Structure */
+    pub fn init(base: u32) -> Dma {
+        return Dma {
+            base,
+            isr:        (base + ISR)        as *mut u32,
+            ifcr:       (base + IFCR)       as *mut u32,
+            cselr:      (base + CSELR)      as *mut u32
+        };
+    }
+
+    /* Configure The Channel (1 - 7), Request Is The DMA_CSELR Value For The Peripheral, The Channel Is Left Disabled */
+    /* The Memory Address Increments And The Peripheral Address Does Not, Circular Mode Is Off */
+    pub fn open(&self, ch: u32, request: u32, dir: Direction, psize: Size, msize: Size, pl: Priority) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        let ccr = self.reg(ch, CCR);
+
+        pointer::clr_ptr_vol_bit_u32(ccr, EN_BIT);
+        pointer::set_ptr_vol_u32(self.cselr, (ch - 1) * CS_OFFSET, CS_MASK, request);
+
+        match dir {
+            Direction::MemToPeriph  =>  pointer::set_ptr_vol_bit_u32(ccr, DIR_BIT),
+            Direction::PeriphToMem  =>  pointer::clr_ptr_vol_bit_u32(ccr, DIR_BIT)
+        }
+
+        pointer::set_ptr_vol_bit_u32(ccr, MINC_BIT);
+        pointer::clr_ptr_vol_bit_u32(ccr, CIRC_BIT);
+        pointer::clr_ptr_vol_bit_u32(ccr, PINC_BIT);
+        pointer::set_ptr_vol_u32(ccr, PSIZE_OFFSET, SIZE_MASK, psize as u32);
+        pointer::set_ptr_vol_u32(ccr, MSIZE_OFFSET, SIZE_MASK, msize as u32);
+        pointer::set_ptr_vol_u32(ccr, PL_OFFSET, PL_MASK, pl as u32);
+    }
+
+    /* Set The Memory Address Increment, Only While The Channel Is Disabled */
+    pub fn set_minc(&self, ch: u32, minc: bool) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        match minc {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.reg(ch, CCR), MINC_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.reg(ch, CCR), MINC_BIT)
+        }
+    }
+
+    /* Set Circular Mode, The Count And Addresses Reload At The End Of The Transfer, Only While The Channel Is Disabled */
+    pub fn set_circular(&self, ch: u32, circ: bool) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        match circ {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.reg(ch, CCR), CIRC_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.reg(ch, CCR), CIRC_BIT)
+        }
+    }
+
+    /* Set The Addresses And Count And Enable The Channel */
+    pub fn start(&self, ch: u32, periph_addr: u32, mem_addr: u32, len: u32) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        let ccr = self.reg(ch, CCR);
+
+        pointer::clr_ptr_vol_bit_u32(ccr, EN_BIT);
+        self.clr_flags(ch);
+        pointer::set_ptr_vol_raw_u32(self.reg(ch, CPAR), periph_addr);
+        pointer::set_ptr_vol_raw_u32(self.reg(ch, CMAR), mem_addr);
+        pointer::set_ptr_vol_u32(self.reg(ch, CNDTR), 0, NDT_MASK, len);
+        pointer::set_ptr_vol_bit_u32(ccr, EN_BIT);
+    }
+
+    /* Disable The Channel */
+    pub fn stop(&self, ch: u32) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        pointer::clr_ptr_vol_bit_u32(self.reg(ch, CCR), EN_BIT);
+    }
+
+    /* Get The Number Of Data Left To Transfer */
+    pub fn get_cnt(&self, ch: u32) -> u32 {
+        if ch == 0 || ch > CHANNELS {
+            return 0;
+        }
+
+        return pointer::get_ptr_vol_u32(self.reg(ch, CNDTR), 0, NDT_MASK);
+    }
+
+    /* Enable The Transfer Complete, Half Transfer And Transfer Error Interrupts */
+    pub fn set_interrupt(&self, ch: u32, tc: bool, ht: bool, te: bool) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        let ccr = self.reg(ch, CCR);
+
+        match tc {
+            true    =>  pointer::set_ptr_vol_bit_u32(ccr, TCIE_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(ccr, TCIE_BIT)
+        }
+
+        match ht {
+            true    =>  pointer::set_ptr_vol_bit_u32(ccr, HTIE_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(ccr, HTIE_BIT)
+        }
+
+        match te {
+            true    =>  pointer::set_ptr_vol_bit_u32(ccr, TEIE_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(ccr, TEIE_BIT)
+        }
+    }
+
+    /* Get The Transfer Complete Flag */
+    pub fn get_tc(&self, ch: u32) -> bool {
+        return self.get_flag(ch, TCIF_BIT);
+    }
+
+    /* Get The Half Transfer Flag */
+    pub fn get_ht(&self, ch: u32) -> bool {
+        return self.get_flag(ch, HTIF_BIT);
+    }
+
+    /* Get The Transfer Error Flag */
+    pub fn get_te(&self, ch: u32) -> bool {
+        return self.get_flag(ch, TEIF_BIT);
+    }
+
+    /* Clear All Flags Of The Channel, Write Only The Channel So Other Flags Are Not Cleared */
+    pub fn clr_flags(&self, ch: u32) {
+        if ch == 0 || ch > CHANNELS {
+            return;
+        }
+
+        pointer::set_ptr_vol_raw_u32(self.ifcr, FLAG_MASK << ((ch - 1) * FLAG_OFFSET));
+    }
+
+    fn get_flag(&self, ch: u32, flag: u32) -> bool {
+        if ch == 0 || ch > CHANNELS {
+            return false;
+        }
+
+        return pointer::get_ptr_vol_bit_u32(self.isr, flag << ((ch - 1) * FLAG_OFFSET));
+    }
+
+    /* Form The Channel Register Pointer */
+    fn reg(&self, ch: u32, offset: u32) -> *mut u32 {
+        return (self.base + offset + ((ch - 1) * CH_SIZE)) as *mut u32;
+    }
+}
@@ -0,0 +1,131 @@
+/* DS18B20 Digital Thermometer (1-Wire) */
+
+use super::common;
+use super::onewire::{crc8, OneWire};
+
+// A conversion is started with CONVERT T, the device holds the bus low while converting
+// and reads back '1' once finished (when externally powered, parasite power needs a strong pull-up instead).
+// Conversion time is 750ms at 12-bit resolution, 375ms at 11-bit, 187.5ms at 10-bit and 93.75ms at 9-bit.
+// The temperature is read from the scratchpad as a signed 16-bit value in 1/16 degrees Celsius.
+// Scratchpad: 0 - 1 Temperature, 2 TH, 3 TL, 4 Configuration, 5 - 7 Reserved, 8 CRC
+
+pub struct Ds18b20 {
+    rom:        [u8; 8]         // ROM Of The Device
+}
+
+/* Enumerations */
+/* Resolution, Configuration Register R1 R0 */
+pub enum Resolution {
+    Bits9,
+    Bits10,
+    Bits11,
+    Bits12
+}
+
+/* Function Commands */
+const CONVERT_T:        u8 = 0x44;
+const WRITE_SCRATCH:    u8 = 0x4E;
+const READ_SCRATCH:     u8 = 0xBE;
+
+pub const FAMILY_CODE:  u8 = 0x28;
+
+const SCRATCH_LEN:      usize = 9;
+const RES_OFFSET:       u32 = 5;
+const RES_RESERVED:     u8 = 0x1F;                      /* Configuration bits 0 - 4 read as 1 */
+const CONFIG_BYTE:      usize = 4;
+const CONFIG_MASK:      u8 = 0x9F;                      /* Configuration bit 7 reads as 0 and bits 0 - 4 as 1, only R1 R0 vary */
+
+/* Check The Scratchpad CRC And The Fixed Configuration Bits */
+/* An All Zero Scratchpad (Bus Stuck Low) Passes The CRC, The Configuration Byte Catches It */
+fn get_valid(scratch: &[u8; SCRATCH_LEN]) -> bool {
+    return crc8(scratch) == 0 && (scratch[CONFIG_BYTE] & CONFIG_MASK) == RES_RESERVED;
+}
+
+impl Ds18b20 {
+    /* Initialize The Structure, None If The ROM Is Not A DS18B20 */
+    pub fn init(rom: [u8; 8]) -> Option<Ds18b20> {
+        if rom[0] != FAMILY_CODE || crc8(&rom) != 0 {
+            return None;
+        }
+        return Some(Ds18b20 { rom });
+    }
+
+    pub fn get_rom(&self) -> [u8; 8] {
+        return self.rom;
+    }
+
+    /* Start A Temperature Conversion, Returns False If The Device Did Not Answer */
+    pub fn start<D: common::DelayUs>(&self, ow: &OneWire<D>) -> bool {
+        if !ow.select(&self.rom) {
+            return false;
+        }
+
+        ow.write_u8(CONVERT_T);
+        return true;
+    }
+
+    /* Start A Temperature Conversion On Every Device On The Bus, Returns False If No Device Answered */
+    pub fn start_all<D: common::DelayUs>(ow: &OneWire<D>) -> bool {
+        if !ow.skip() {
+            return false;
+        }
+
+        ow.write_u8(CONVERT_T);
+        return true;
+    }
+
+    /* Poll The Conversion, Only Valid Directly After Start While Externally Powered */
+    pub fn done<D: common::DelayUs>(ow: &OneWire<D>) -> bool {
+        return ow.read_bit();
+    }
+
+    /* Read The Temperature In 1/16 Degrees Celsius, None If The Device Did Not Answer Or The Scratchpad Is Invalid */
+    pub fn read<D: common::DelayUs>(&self, ow: &OneWire<D>) -> Option<i16> {
+        let mut scratch = [0; SCRATCH_LEN];
+
+        if !ow.select(&self.rom) {
+            return None;
+        }
+
+        ow.write_u8(READ_SCRATCH);
+        ow.read(&mut scratch);
+
+        if !get_valid(&scratch) {
+            return None;
+        }
+        return Some(i16::from_le_bytes([scratch[0], scratch[1]]));
+    }
+
+    /* Read The Temperature In Milli Degrees Celsius */
+    pub fn read_milli_c<D: common::DelayUs>(&self, ow: &OneWire<D>) -> Option<i32> {
+        return self.read(ow).map(|raw| (raw as i32 * 1000) / 16);
+    }
+
+    /* Set The Alarm Limits And Resolution, Returns False If The Device Did Not Answer */
+    pub fn set_resolution<D: common::DelayUs>(&self, ow: &OneWire<D>, th: i8, tl: i8, res: Resolution) -> bool {
+        if !ow.select(&self.rom) {
+            return false;
+        }
+
+        ow.write_u8(WRITE_SCRATCH);
+        ow.write(&[th as u8, tl as u8, ((res as u8) << RES_OFFSET) | RES_RESERVED]);
+        return true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::get_valid;
+
+    #[test]
+    fn scratchpad() {
+        let good = [0x50, 0x05, 0x4B, 0x46, 0x7F, 0xFF, 0x0C, 0x10, 0x1C];   // 85 C power on value, 12-bit
+        let mut bad_crc = good;
+        bad_crc[0] ^= 0x01;
+
+        assert!(get_valid(&good));
+        assert!(!get_valid(&bad_crc));
+        assert!(!get_valid(&[0; 9]));                                      // Passes the CRC, config byte is wrong
+        assert!(!get_valid(&[0xFF; 9]));
+    }
+}
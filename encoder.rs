@@ -0,0 +1,76 @@
+/* Quadrature Encoder Position */
+
+use super::{common, timer};
+
+// The timer counter in encoder mode is only 16 or 32 bits wide and wraps at ARR.
+// The position is extended to 64 bits in software, the difference between two counter reads is taken
+// at the counter width and added to the position, so ARR must be the counter maximum and the update must be polled
+// at least once every half counter range (32768 counts on a 16-bit timer) to see the direction of a wrap correctly.
+// Polling deadline: half range / maximum count rate, 4 counts per line at 1000 lines and 3000 rpm is 200 kcounts/s,
+// so a 16-bit timer must be polled every 163ms and a 32-bit timer every 3 hours.
+// Calling update only from the timer update interrupt does not meet the deadline by itself: the interrupt fires once
+// per full range, which the difference at the counter width reads as no movement. Counting the wraps in the interrupt
+// by UIF and DIR would work without polling, this driver polls instead so it also runs with the interrupt disabled.
+// Index pulse: a third channel set up as an input capture on the index signal latches the counter when the index passes,
+// the next update moves the zero of the position to the latched count.
+
+pub struct Encoder {
+    position:   i64,                    // Extended Position In Counts
+    last:       u32,                    // Counter Value At The Last Update
+    wide:       bool,                   // 32-Bit Counter
+    index:      Option<timer::Channel>  // Channel Capturing The Index Pulse
+}
+
+impl Encoder {
+    /* Initialize The Structure, The Timer Must Already Be Set Up In Encoder Mode */
+    pub fn init(tim: &timer::Timer, index: Option<timer::Channel>) -> Encoder {
+        return Encoder {
+            position:   0,
+            last:       tim.get_cnt(),
+            wide:       tim.get_arr() > common::MASK_16_BIT,
+            index
+        };
+    }
+
+    /* Read The Counter And Extend The Position */
+    /* Must Be Called Before The Encoder Moves Half A Counter Range (32768 Counts On 16-Bit, 2^31 On 32-Bit) */
+    /* Since The Last Call, More Movement Is Read As A Move In The Opposite Direction */
+    pub fn update(&mut self, tim: &timer::Timer) -> i64 {
+        let cnt = tim.get_cnt();
+
+        self.position += self.delta(self.last, cnt);
+        self.last = cnt;
+
+        if let Some(ch) = self.index {
+            if let Some((at, _)) = tim.get_capture(ch) {
+                self.position = self.delta(at, cnt);
+            }
+        }
+
+        return self.position;
+    }
+
+    /* Get The 64-Bit Position As Of The Last Update */
+    pub fn get_position(&self) -> i64 {
+        return self.position;
+    }
+
+    /* Get The Position As Of The Last Update Wrapped To 32 Bits */
+    pub fn get_position_i32(&self) -> i32 {
+        return self.position as i32;
+    }
+
+    /* Set The Position, The Counter Is Left Running */
+    pub fn set_position(&mut self, tim: &timer::Timer, position: i64) {
+        self.last = tim.get_cnt();
+        self.position = position;
+    }
+
+    /* Signed Counts From First To Second At The Counter Width */
+    fn delta(&self, first: u32, second: u32) -> i64 {
+        if self.wide {
+            return second.wrapping_sub(first) as i32 as i64;
+        }
+        return (second.wrapping_sub(first) as u16) as i16 as i64;
+    }
+}
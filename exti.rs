@@ -0,0 +1,174 @@
+/* Extended Interrupts And Events Controller (EXTI) */
+
+use super::{gpio, pointer, syscfg};
+
+// The GPIOs are connected to the 16 configurable interrupt/event lines.
+// Each line is routed to one of the GPIO ports through the SYSCFG_EXTICR registers,
+// so only one port can drive a given line number at a time (PA0 and PB0 both use line 0).
+// To generate the interrupt:
+// • Route the port to the line in SYSCFG_EXTICR (SYSCFG clock enabled in RCC).
+// • Configure the trigger selection bits (EXTI_RTSR1 and EXTI_FTSR1).
+// • Unmask the line in EXTI_IMR1 and enable the NVIC IRQ channel mapped to the line.
+// When the selected edge occurs on the line, the pending bit in EXTI_PR1 is set and must be cleared
+// by writing a '1' to it, writing '0' has no effect.
+
+pub struct Exti {
+    imr1:       *mut u32,       // Interrupt Mask Register 1
+    emr1:       *mut u32,       // Event Mask Register 1
+    rtsr1:      *mut u32,       // Rising Trigger Selection Register 1
+    ftsr1:      *mut u32,       // Falling Trigger Selection Register 1
+    swier1:     *mut u32,       // Software Interrupt Event Register 1
+    pr1:        *mut u32,       // Pending Register 1
+}
+
+/* Register Offset */
+const IMR1:     u32 = 0x00;
+const EMR1:     u32 = 0x04;
+const RTSR1:    u32 = 0x08;
+const FTSR1:    u32 = 0x0C;
+const SWIER1:   u32 = 0x10;
+const PR1:      u32 = 0x14;
+
+/* Enumerations */
+/* Edge That Triggers The Line */
+pub enum Edge {
+    Rising,
+    Falling,
+    Both
+}
+
+/* NVIC Position Of The EXTI Lines */
+const EXTI0_IRQ:        u32 = 6;
+const EXTI1_IRQ:        u32 = 7;
+const EXTI2_IRQ:        u32 = 8;
+const EXTI3_IRQ:        u32 = 9;
+const EXTI4_IRQ:        u32 = 10;
+const EXTI9_5_IRQ:      u32 = 23;
+const EXTI15_10_IRQ:    u32 = 40;
+
+const EXTI_LINES:       u32 = 16;
+
+/* Get The NVIC IRQ Number Of A GPIO EXTI Line, Lines 5 - 9 And 10 - 15 Share An IRQ */
+pub fn irq(line: u32) -> Option<u32> {
+    return match line {
+        0       => Some(EXTI0_IRQ),
+        1       => Some(EXTI1_IRQ),
+        2       => Some(EXTI2_IRQ),
+        3       => Some(EXTI3_IRQ),
+        4       => Some(EXTI4_IRQ),
+        5..=9   => Some(EXTI9_5_IRQ),
+        10..=15 => Some(EXTI15_10_IRQ),
+        _       => None
+    };
+}
+
+impl Exti {
+    /* Initialize The Structure */
+    pub fn init(base: u32) -> Exti {
+        return Exti {
+            imr1:       (base + IMR1)       as *mut u32,
+            emr1:       (base + EMR1)       as *mut u32,
+            rtsr1:      (base + RTSR1)      as *mut u32,
+            ftsr1:      (base + FTSR1)      as *mut u32,
+            swier1:     (base + SWIER1)     as *mut u32,
+            pr1:        (base + PR1)        as *mut u32
+        };
+    }
+
+    /* Route The Port To The Line And Select The Edge, The Line Is Left Masked */
+    pub fn open(&self, syscfg: &syscfg::Syscfg, port: gpio::Port, line: u32, edge: Edge) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        self.clr_interrupt(line);
+        syscfg.set_exti(line, port);
+        self.set_edge(line, edge);
+        self.clr_pend(line);
+    }
+
+    /* Select The Trigger Edge Of The Line */
+    pub fn set_edge(&self, line: u32, edge: Edge) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        let bit = 1 << line;
+
+        match edge {
+            Edge::Rising => {
+                pointer::set_ptr_vol_bit_u32(self.rtsr1, bit);
+                pointer::clr_ptr_vol_bit_u32(self.ftsr1, bit);
+            } Edge::Falling => {
+                pointer::clr_ptr_vol_bit_u32(self.rtsr1, bit);
+                pointer::set_ptr_vol_bit_u32(self.ftsr1, bit);
+            } Edge::Both => {
+                pointer::set_ptr_vol_bit_u32(self.rtsr1, bit);
+                pointer::set_ptr_vol_bit_u32(self.ftsr1, bit);
+            }
+        }
+    }
+
+    /* Unmask The Interrupt Of The Line */
+    pub fn set_interrupt(&self, line: u32) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        pointer::set_ptr_vol_bit_u32(self.imr1, 1 << line);
+    }
+
+    /* Mask The Interrupt Of The Line */
+    pub fn clr_interrupt(&self, line: u32) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        pointer::clr_ptr_vol_bit_u32(self.imr1, 1 << line);
+    }
+
+    /* Unmask The Event Of The Line */
+    pub fn set_event(&self, line: u32) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        pointer::set_ptr_vol_bit_u32(self.emr1, 1 << line);
+    }
+
+    /* Mask The Event Of The Line */
+    pub fn clr_event(&self, line: u32) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        pointer::clr_ptr_vol_bit_u32(self.emr1, 1 << line);
+    }
+
+    /* Get The Pending Flag Of The Line */
+    pub fn get_pend(&self, line: u32) -> bool {
+        if line >= EXTI_LINES {
+            return false;
+        }
+
+        return pointer::get_ptr_vol_bit_u32(self.pr1, 1 << line);
+    }
+
+    /* Clear The Pending Flag Of The Line, Write Only The Line So Other Pending Flags Are Not Cleared */
+    pub fn clr_pend(&self, line: u32) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        pointer::set_ptr_vol_raw_u32(self.pr1, 1 << line);
+    }
+
+    /* Trigger The Line From Software */
+    pub fn set_swi(&self, line: u32) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        pointer::set_ptr_vol_raw_u32(self.swier1, 1 << line);
+    }
+}
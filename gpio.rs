@@ -1,7 +1,7 @@
 /* General Purpose I/O */
 /* Manual Page 235 */
 
-use super::{common, pointer};
+use super::{af, common, pointer};
 
 pub struct Gpio {
     moder:              *mut u32,           // Mode Register
@@ -15,6 +15,7 @@ pub struct Gpio {
     afrl:               *mut u32,           // Alternate Function Low Register
     afrh:               *mut u32,           // Alternate Function High Register
     brr:                *mut u32,           // Bit Reset Register
+    ascr:               *mut u32,           // Analog Switch Control Register (STM32L47x/L48x Only)
 }
 
 /* Register Offset */
@@ -29,8 +30,26 @@ const LCKR:     u32 = 0x1C;
 const AFRL:     u32 = 0x20;
 const AFRH:     u32 = 0x24;
 const BRR:      u32 = 0x28;
+const ASCR:     u32 = 0x2C;
+
+/* Port Base Addresses, GPIOA At 0x4800 0000 And One Port Every 0x400 */
+const PORT_BASE:    u32 = 0x4800_0000;
+const PORT_STRIDE:  u32 = 0x400;
 
 /* Enumerations */
+// GPIO Port, 000: PA     001: PB     010: PC     011: PD     100: PE     101: PF     110: PG     111: PH
+#[derive(Clone, Copy, PartialEq)]
+pub enum Port {
+    PortA,
+    PortB,
+    PortC,
+    PortD,
+    PortE,
+    PortF,
+    PortG,
+    PortH
+}
+
 // 00: Input mode     01: General purpose output mode     10: Alternate function mode     11: Analog mode (reset state)
 pub enum Mode {
     In,
@@ -63,6 +82,7 @@ pub enum Pupd {
 /* 0000: AF0     0001: AF1     0010: AF2     0011: AF3     0100: AF4     0101: AF5
    0110: AF6     0111: AF7     1000: AF8     1001: AF9     1010: AF10    1011: AF11
    1100: AF12    1101: AF13    1110: AF14    1111: AF15                             */
+#[derive(Clone, Copy, PartialEq)]
 pub enum AltFunc {
     Af0,
     Af1,
@@ -82,17 +102,29 @@ pub enum AltFunc {
     Af15
 }
 
+/* ADC Input The Analog Pin Is Connected To */
+pub struct AdcIn {
+    channel:    u32             // ADCx_INx Channel Number
+}
+
 /* Register Masks */
 const MODER_MASK:       u32 = common::MASK_2_BIT;       /* Mode is mask required, here we set the mask to two bit 11 */
 const OSPEED_MASK:      u32 = common::MASK_2_BIT;       /* Mode is mask required, here we set the mask to two bit 11 */
 const PUPD_MASK:        u32 = common::MASK_2_BIT;       /* Mode is mask required, here we set the mask to two bit 11 */
 const AF_MASK:          u32 = common::MASK_4_BIT;       /* Mode is mask required, here we set the mask to four bit 1111 */
+const BS_MASK:          u32 = common::MASK_16_BIT;      /* BSRR lower half word sets the pin, 1 bit per pin */
+const BR_MASK:          u32 = common::MASK_16_BIT;      /* BSRR upper half word and BRR reset the pin, 1 bit per pin */
+const LCK_MASK:         u32 = common::MASK_16_BIT;      /* LCKR lower half word selects the pins to lock, 1 bit per pin */
+
+/* Register Bits */
+const LCKK_BIT:         u32 = common::BIT_16;           /* Lock Key, 0 = Port configuration lock key not active, 1 = Port configuration lock key active */
 
 /* Register Offsets */
 const MODER_OFFSET:     u32 = 2;                        /* Mode is two bits wide, shift by an offset of 2 */
 const OSPEED_OFFSET:    u32 = 2;                        /* Output Speed is two bits wide, shift by an offset of 2 */
 const PUPD_OFFSET:      u32 = 2;                        /* Pu Speed is two bits wide, shift by an offset of 2 */
 const AF_OFFSET:        u32 = 4;                        /* Alternate Function is four bits wide, shift by an offset of 4 */
+const BR_OFFSET:        u32 = 16;                       /* BSRR reset bits start at bit 16 */
 
 impl Gpio {
     /* Initialize The Structure */
@@ -108,7 +140,29 @@ impl Gpio {
             lckr:       (base + LCKR)       as *mut u32,
             afrl:       (base + AFRL)       as *mut u32,
             afrh:       (base + AFRH)       as *mut u32,
-            brr:        (base + BRR)        as *mut u32
+            brr:        (base + BRR)        as *mut u32,
+            ascr:       (base + ASCR)       as *mut u32
+        };
+    }
+
+    /* Get The Port From The Base Address, None If The Base Is Not A GPIO Port */
+    pub fn get_port(&self) -> Option<Port> {
+        let base = self.moder as usize as u32 - MODER;
+
+        if base < PORT_BASE || !(base - PORT_BASE).is_multiple_of(PORT_STRIDE) {
+            return None;
+        }
+
+        return match (base - PORT_BASE) / PORT_STRIDE {
+            0 => Some(Port::PortA),
+            1 => Some(Port::PortB),
+            2 => Some(Port::PortC),
+            3 => Some(Port::PortD),
+            4 => Some(Port::PortE),
+            5 => Some(Port::PortF),
+            6 => Some(Port::PortG),
+            7 => Some(Port::PortH),
+            _ => None
         };
     }
 
@@ -117,24 +171,67 @@ impl Gpio {
         return pointer::get_ptr_vol_bit_u32(self.idr, val);
     }
 
-    /* Set The Pin */
+    /* Get The Output State Of The Pin */
+    pub fn get_output(&self, val: u32) -> bool {
+        return pointer::get_ptr_vol_bit_u32(self.odr, val);
+    }
+
+    /* Set The Pin, Single Write To BSRR So It Can Not Race An Interrupt */
     pub fn set_pin(&self, val: u32) {
-        pointer::set_ptr_vol_bit_u32(self.odr, val);
+        pointer::set_ptr_vol_raw_u32(self.bsrr, val & BS_MASK);
     }
 
-    /* Clear The Pin */
+    /* Clear The Pin, Single Write To BRR So It Can Not Race An Interrupt */
     pub fn clr_pin(&self, val: u32) {
-        pointer::clr_ptr_vol_bit_u32(self.odr, val);
+        pointer::set_ptr_vol_raw_u32(self.brr, val & BR_MASK);
+    }
+
+    /* Toggle The Pin, Pins High Are Reset And Pins Low Are Set In One BSRR Write */
+    pub fn toggle_pin(&self, val: u32) {
+        let odr = pointer::get_ptr_vol_raw_u32(self.odr);
+
+        pointer::set_ptr_vol_raw_u32(self.bsrr, (((odr & val) & BR_MASK) << BR_OFFSET) | ((!odr & val) & BS_MASK));
     }
 
-    /* Set Lock */
-    pub fn set_lock(&self, val: u32){
-        pointer::set_ptr_vol_bit_u32(self.lckr, val);
+    /* Set And Clear Multiple Pins In One BSRR Write, If A Pin Is In Both Masks The Set Wins */
+    pub fn write_masked(&self, set_mask: u32, clear_mask: u32) {
+        pointer::set_ptr_vol_raw_u32(self.bsrr, ((clear_mask & BR_MASK) << BR_OFFSET) | (set_mask & BS_MASK));
     }
-    
-    /* Clear Lock */
-    pub fn clr_lock(&self, val: u32){
-        pointer::clr_ptr_vol_bit_u32(self.lckr, val);
+
+    // Lock key write sequence:
+    //      WR LCKR[16] = '1' + LCKR[15:0]
+    //      WR LCKR[16] = '0' + LCKR[15:0]
+    //      WR LCKR[16] = '1' + LCKR[15:0]
+    //      RD LCKR
+    //      RD LCKR[16] = '1' (this read operation is optional but it confirms that the lock is active)
+    // During the LOCK key write sequence, the value of LCK[15:0] must not change.
+    // Any error in the lock key write sequence aborts the lock.
+    // After the first lock sequence on any bit of the port, any read access on the LCKK bit will return '1' until the next MCU reset or peripheral reset.
+    // There is no unlock, the configuration of the locked pins is frozen until the next reset.
+    /* Lock The Configuration Of The Pins In The Mask, Returns False If The Port Did Not Lock */
+    pub fn set_lock(&self, val: u32) -> bool {
+        if self.get_port_lock() {
+            return false;                                                   // Port already locked, LCKR is frozen until reset
+        }
+
+        let lck = val & LCK_MASK;
+
+        pointer::set_ptr_vol_raw_u32(self.lckr, LCKK_BIT | lck);
+        pointer::set_ptr_vol_raw_u32(self.lckr, lck);
+        pointer::set_ptr_vol_raw_u32(self.lckr, LCKK_BIT | lck);
+        pointer::get_ptr_vol_raw_u32(self.lckr);
+
+        return self.get_port_lock();
+    }
+
+    /* Get If The Lock Key Is Active On The Port */
+    pub fn get_port_lock(&self) -> bool {
+        return pointer::get_ptr_vol_bit_u32(self.lckr, LCKK_BIT);
+    }
+
+    /* Get If The Configuration Of The Pin Is Locked */
+    pub fn get_lock(&self, val: u32) -> bool {
+        return self.get_port_lock() && pointer::get_ptr_vol_bit_u32(self.lckr, val & LCK_MASK);
     }
 
     /* Set Pin Type And Alternate Function */
@@ -162,6 +259,47 @@ impl Gpio {
         }
     }
     
+    /* Set Pin As The Alternate Function Carrying The Signal, Returns False If The Pin Can Not Carry The Signal On The Chip */
+    pub fn set_signal(&self, chip: af::Chip, bit: u32, signal: af::Signal, otype: OType) -> bool {
+        let port = match self.get_port() {
+            Some(port) => port,
+            None => return false
+        };
+
+        return match af::lookup(chip, port, bit, signal) {
+            Some(alt_func) => {
+                self.otype(bit, Mode::Alt, otype, alt_func);
+                true
+            },
+            None => false
+        };
+    }
+
+    /* Set Pin As An ADC Input, Analog Mode, No Pull And Analog Switch Closed Where The Chip Has One */
+    /* Returns The ADC Input The Pin Maps To, None If The Pin Is Not An ADC Input */
+    pub fn set_analog(&self, chip: af::Chip, bit: u32) -> Option<AdcIn> {
+        let channel = af::adc_channel(self.get_port()?, bit)?;
+
+        self.pupd(bit, Pupd::NoPuPd);
+        pointer::set_ptr_vol_u32(self.moder, bit * MODER_OFFSET, MODER_MASK, Mode::An as u32);
+
+        if af::has_ascr(chip) {
+            self.set_analog_switch(bit);
+        }
+
+        return Some(AdcIn { channel });
+    }
+
+    /* Close The Analog Switch, Connects The Pin To The ADC (STM32L47x/L48x Only) */
+    pub fn set_analog_switch(&self, bit: u32) {
+        pointer::set_ptr_vol_bit_u32(self.ascr, 1 << bit);
+    }
+
+    /* Open The Analog Switch, Disconnects The Pin From The ADC (STM32L47x/L48x Only) */
+    pub fn clr_analog_switch(&self, bit: u32) {
+        pointer::clr_ptr_vol_bit_u32(self.ascr, 1 << bit);
+    }
+
     /* Set Pin Speed */
     pub fn ospeed(&self, bit: u32, speed: OSpeed) {
         pointer::set_ptr_vol_u32(self.ospeedr, bit * OSPEED_OFFSET, OSPEED_MASK, speed as u32);
@@ -172,3 +310,9 @@ impl Gpio {
         pointer::set_ptr_vol_u32(self.pupdr, bit * PUPD_OFFSET, PUPD_MASK, mode as u32);
     }
 }
+
+impl AdcIn {
+    pub fn get_channel(&self) -> u32 {
+        return self.channel;
+    }
+}
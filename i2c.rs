@@ -91,6 +91,13 @@ const LEN_1_BYTE:       u32 = 1;
 
 const TIMEOUT:          u32 = common::WAIT100US;
 
+/* Transfer Level API, Implemented By The Hardware And The Bit Banged Master So Device Drivers Work On Either */
+pub trait I2cBus {
+    fn std_write(&self, slave_addr: u32, addr_10bit: bool, req_10bit: bool, buf: &[u8]);
+    fn std_read(&self, slave_addr: u32, addr_10bit: bool, req_10bit: bool, buf_write: &[u8], buf_read: &mut [u8]);
+    fn std_read_u8(&self, slave_addr: u32, addr_10bit: bool, req_10bit: bool, byte_write: u8) -> u8;
+}
+
 impl I2c { 
     /* Initialize The Structure */
     pub fn init(base: u32) -> I2c {
@@ -374,3 +381,17 @@ impl I2c {
         pointer::set_ptr_vol_u32(self.timingr, PRESC_OFFSET, DEL_MASK, presc);
     }
 }
+
+impl I2cBus for I2c {
+    fn std_write(&self, slave_addr: u32, addr_10bit: bool, req_10bit: bool, buf: &[u8]) {
+        I2c::std_write(self, slave_addr, addr_10bit, req_10bit, buf);
+    }
+
+    fn std_read(&self, slave_addr: u32, addr_10bit: bool, req_10bit: bool, buf_write: &[u8], buf_read: &mut [u8]) {
+        I2c::std_read(self, slave_addr, addr_10bit, req_10bit, buf_write, buf_read);
+    }
+
+    fn std_read_u8(&self, slave_addr: u32, addr_10bit: bool, req_10bit: bool, byte_write: u8) -> u8 {
+        return I2c::std_read_u8(self, slave_addr, addr_10bit, req_10bit, byte_write);
+    }
+}
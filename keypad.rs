@@ -0,0 +1,222 @@
+/* Keypad Matrix Scanner */
+
+use super::{debounce, exti, gpio, syscfg};
+
+// Row pins are open drain outputs, column pins are inputs with pull-ups, a pressed key connects its row to its column.
+// One row is driven low at a time and the columns reading low are the pressed keys of that row.
+// The key number is row * columns + column, every key is tracked on its own (n-key rollover) up to 32 keys,
+// a larger matrix fails to compile.
+// Debounce: each key has its own debounce filter, a key only changes state once the scans have agreed on the new state
+// the debounce number of times in a row.
+// Ghosting: without a diode per key, three keys pressed on the corners of a rectangle make the fourth corner read as pressed.
+// Any two rows sharing two or more pressed columns can not be told apart from a ghost, the scan is dropped and the keys hold their state.
+// Sleep: drive every row low and unmask the column EXTI lines on the falling edge, any key press wakes the core.
+
+pub struct Keypad<'a, const R: usize, const C: usize> {
+    row_port:   &'a gpio::Gpio,     // Port Of The Row Pins
+    rows:       [u32; R],           // Row Pin Numbers
+    col_port:   &'a gpio::Gpio,     // Port Of The Column Pins
+    cols:       [u32; C],           // Column Pin Numbers
+    state:      u32,                // Debounced State, 1 Bit Per Key, 1 = Pressed
+    keys:       [debounce::Debounce; MAX_KEYS],
+    ghost:      bool,               // Last Scan Was Dropped Due To Ghosting
+    queue:      [KeyEvent; QUEUE_LEN],
+    head:       usize,              // Next Event To Pop
+    len:        usize,              // Events In The Queue
+    overflow:   bool                // Events Were Dropped Because The Queue Was Full
+}
+
+/* Key Event */
+#[derive(Clone, Copy)]
+pub struct KeyEvent {
+    key:        u8,                 // Key Number, Row * Columns + Column
+    pressed:    bool                // True = Pressed, False = Released
+}
+
+const QUEUE_LEN:        usize = 16;
+const MAX_KEYS:         usize = 32;
+const SETTLE:           u32 = 100;                      /* Spin Count For The Column Lines To Settle After A Row Changes */
+
+impl<'a, const R: usize, const C: usize> Keypad<'a, R, C> {
+    /* Keys In The Matrix, Must Fit The 32-Bit State */
+    const KEYS: usize = {
+        assert!(R * C <= MAX_KEYS, "keypad matrix has more than 32 keys");
+        R * C
+    };
+
+    /* Initialize The Structure, Every Row Is Released, The Pins Must Already Be Configured */
+    pub fn init(row_port: &'a gpio::Gpio, rows: [u32; R], col_port: &'a gpio::Gpio, cols: [u32; C], debounce: u8) -> Keypad<'a, R, C> {
+        let _ = Self::KEYS;                                 // Evaluate the size check for every matrix that is built
+
+        let kp = Keypad {
+            row_port,
+            rows,
+            col_port,
+            cols,
+            state:      0,
+            keys:       [debounce::Debounce::init(debounce as u32); MAX_KEYS],
+            ghost:      false,
+            queue:      [KeyEvent { key: 0, pressed: false }; QUEUE_LEN],
+            head:       0,
+            len:        0,
+            overflow:   false
+        };
+
+        kp.release_rows();
+        return kp;
+    }
+
+    /* Scan The Matrix And Update The Key States, Call Periodically */
+    pub fn scan(&mut self) {
+        let raw = self.read_matrix();
+
+        self.update(raw);
+    }
+
+    /* Read The Raw Key States, 1 Bit Per Key, 1 = Pressed */
+    pub fn read_matrix(&self) -> u32 {
+        let mut raw = 0;
+
+        for (r, row) in self.rows.iter().enumerate() {
+            self.row_port.clr_pin(1 << *row);
+            settle();
+
+            for (c, col) in self.cols.iter().enumerate() {
+                if !self.col_port.get_pin(1 << *col) {
+                    raw |= 1 << ((r * C) + c);
+                }
+            }
+
+            self.row_port.set_pin(1 << *row);
+        }
+        settle();
+
+        return raw;
+    }
+
+    /* Run The Debounce On A Raw Scan, 1 Bit Per Key, 1 = Pressed */
+    pub fn update(&mut self, raw: u32) {
+        self.ghost = ghosting::<R, C>(raw);
+
+        if self.ghost {
+            return;
+        }
+
+        for key in 0..Self::KEYS {
+            let bit = 1 << key;
+
+            if let Some(pressed) = self.keys[key].update((raw & bit) != 0) {
+                self.state ^= bit;
+                self.push(KeyEvent { key: key as u8, pressed });
+            }
+        }
+    }
+
+    /* Get The Next Key Event */
+    pub fn pop(&mut self) -> Option<KeyEvent> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let event = self.queue[self.head];
+        self.head = (self.head + 1) % QUEUE_LEN;
+        self.len -= 1;
+        return Some(event);
+    }
+
+    /* Get The Debounced State, 1 Bit Per Key, 1 = Pressed */
+    pub fn get_state(&self) -> u32 {
+        return self.state;
+    }
+
+    /* Get If The Key Is Pressed */
+    pub fn get_key(&self, row: usize, col: usize) -> bool {
+        return (self.state & (1 << ((row * C) + col))) != 0;
+    }
+
+    /* Get If The Last Scan Was Dropped Due To Ghosting */
+    pub fn get_ghost(&self) -> bool {
+        return self.ghost;
+    }
+
+    /* Get And Clear The Queue Overflow Flag */
+    pub fn get_overflow(&mut self) -> bool {
+        let overflow = self.overflow;
+        self.overflow = false;
+        return overflow;
+    }
+
+    /* Drive Every Row Low And Unmask The Column Lines, Call Before Sleeping, Any Key Press Raises An EXTI Interrupt */
+    pub fn set_wake(&self, exti: &exti::Exti, syscfg: &syscfg::Syscfg, col_port: gpio::Port) {
+        for row in self.rows.iter() {
+            self.row_port.clr_pin(1 << *row);
+        }
+
+        for col in self.cols.iter() {
+            exti.open(syscfg, col_port, *col, exti::Edge::Falling);
+            exti.set_interrupt(*col);
+        }
+    }
+
+    /* Mask The Column Lines And Release The Rows, Call After Waking Before Scanning */
+    pub fn clr_wake(&self, exti: &exti::Exti) {
+        for col in self.cols.iter() {
+            exti.clr_interrupt(*col);
+            exti.clr_pend(*col);
+        }
+
+        self.release_rows();
+    }
+
+    fn release_rows(&self) {
+        for row in self.rows.iter() {
+            self.row_port.set_pin(1 << *row);
+        }
+    }
+
+    fn push(&mut self, event: KeyEvent) {
+        if self.len == QUEUE_LEN {
+            self.overflow = true;
+            return;
+        }
+
+        self.queue[(self.head + self.len) % QUEUE_LEN] = event;
+        self.len += 1;
+    }
+}
+
+impl KeyEvent {
+    pub fn get_key(&self) -> u8 {
+        return self.key;
+    }
+
+    pub fn get_pressed(&self) -> bool {
+        return self.pressed;
+    }
+}
+
+/* Two Rows Sharing Two Or More Pressed Columns Can Not Be Told Apart From A Ghost */
+fn ghosting<const R: usize, const C: usize>(raw: u32) -> bool {
+    let row_mask: u32 = if C >= 32 { u32::MAX } else { (1 << C) - 1 };
+
+    for a in 0..R {
+        let cols_a = (raw >> (a * C)) & row_mask;
+
+        if cols_a.count_ones() < 2 {
+            continue;
+        }
+
+        for b in (a + 1)..R {
+            if (cols_a & (raw >> (b * C)) & row_mask).count_ones() >= 2 {
+                return true;
+            }
+        }
+    }
+    return false;
+}
+
+fn settle() {
+    for _ in 0..SETTLE {
+        core::hint::spin_loop();
+    }
+}
@@ -1,13 +1,54 @@
 /* Public Modules */
+// Functions end in an explicit return, the HAL style, so clippy::needless_return is allowed per module
+// on the drivers that follow it rather than crate-wide, any other lint in those modules is still reported.
+#[allow(clippy::needless_return)]
+pub mod af;
 pub mod common;
+#[allow(clippy::needless_return)]
 pub mod timer;
+#[allow(clippy::needless_return)]
 pub mod usart;
+#[allow(clippy::needless_return)]
 pub mod gpio;
 pub mod nvic;
 pub mod rcc;
+#[allow(clippy::needless_return)]
 pub mod i2c;
+#[allow(clippy::needless_return)]
 pub mod spi;
 pub mod can;
+#[allow(clippy::needless_return)]
+pub mod exti;
+#[allow(clippy::needless_return)]
+pub mod syscfg;
+#[allow(clippy::needless_return)]
+pub mod debounce;
+#[allow(clippy::needless_return)]
+pub mod onewire;
+#[allow(clippy::needless_return)]
+pub mod ds18b20;
+#[allow(clippy::needless_return)]
+pub mod softi2c;
+#[allow(clippy::needless_return)]
+pub mod softspi;
+#[allow(clippy::needless_return)]
+pub mod dma;
+#[allow(clippy::needless_return)]
+pub mod ws2812;
+#[allow(clippy::needless_return)]
+pub mod keypad;
+#[allow(clippy::needless_return)]
+pub mod encoder;
+#[allow(clippy::needless_return)]
+pub mod burst;
+#[allow(clippy::needless_return)]
+pub mod monotonic;
+#[allow(clippy::needless_return)]
+pub mod softtimer;
+#[allow(clippy::needless_return)]
+pub mod systick;
+#[allow(clippy::needless_return)]
+pub mod ring;
 
 /* Private Modules */
 pub mod pointer;
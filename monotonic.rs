@@ -0,0 +1,169 @@
+/* 64-Bit Monotonic Timebase On A Free Running Timer */
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use core::time::Duration;
+use super::{common, timer};
+
+// The timer counts up from 0 to its maximum ARR and wraps, the update interrupt counts the wraps.
+// Time in ticks = wraps x (ARR + 1) + CNT, which needs a consistent pair of the wrap count and the counter:
+// • The wrap count is 64 bits kept as two 32-bit halves (the core has no 64-bit atomics), the interrupt increments
+//   the low half and carries into the high half, so the count never overflows even on a 16-bit timer.
+// • Both halves are read before and after the counter, if the interrupt ran in between the read is repeated.
+//   Readers must not preempt the update interrupt (call from main or a lower priority), or they can see a half done carry.
+// • If the counter wrapped but the interrupt has not run yet (interrupts masked or a higher priority running),
+//   the update flag is still set and a small counter value belongs to the next wrap, so one wrap is added.
+// This holds as long as the update interrupt is not held off for more than half a counter range.
+// The structure only holds atomics and constants, so it can be a static shared between the interrupt and the main code.
+
+pub struct Monotonic {
+    wraps_lo:   AtomicU32,      // Counter Wraps Seen By The Update Interrupt, Low Half
+    wraps_hi:   AtomicU32,      // Counter Wraps Seen By The Update Interrupt, High Half
+    range:      u64,            // Counts Per Wrap, ARR + 1
+    tick_hz:    u32             // Counter Frequency
+}
+
+/* Point In Time Since The Timebase Started, In Nanoseconds */
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant {
+    ns:         u64
+}
+
+const NS_PER_S:         u128 = 1_000_000_000;
+
+impl Monotonic {
+    /* Initialize The Structure, Tick Is The Counter Frequency In Hz, Width Is The Counter Width Of The Timer */
+    pub const fn init(tick_hz: u32, width: timer::Width) -> Monotonic {
+        let range = match width {
+            timer::Width::Bits16    =>  common::MASK_16_BIT as u64 + 1,
+            timer::Width::Bits32    =>  common::MASK_32_BIT as u64 + 1
+        };
+
+        return Monotonic {
+            wraps_lo:   AtomicU32::new(0),
+            wraps_hi:   AtomicU32::new(0),
+            range,
+            tick_hz
+        };
+    }
+
+    /* Set Up The Timer To Count At The Tick Frequency Over Its Full Range And Start It, Timer Clock In Hz */
+    /* Returns False If The Tick Frequency Is Not A Whole Division Of The Timer Clock Reachable By The Prescaler */
+    pub fn start(&self, tim: &timer::Timer, clk_hz: u32) -> bool {
+        if self.tick_hz == 0 || !clk_hz.is_multiple_of(self.tick_hz) || (clk_hz / self.tick_hz) > common::MASK_16_BIT + 1 {
+            return false;
+        }
+
+        tim.stop();
+        tim.open(timer::TimerType::Cont, timer::Direction::Upcount);
+        tim.set_psc((clk_hz / self.tick_hz) - 1);
+        tim.set_arr((self.range - 1) as u32);
+        tim.set_update_event();                     // Load PSC now, not after the first wrap
+        self.wraps_lo.store(0, Ordering::Relaxed);
+        self.wraps_hi.store(0, Ordering::Relaxed);
+        tim.set_interrupt();
+        tim.start();
+        return true;
+    }
+
+    /* Count A Wrap, Call From The Update Interrupt Of The Timer */
+    pub fn on_update(&self, tim: &timer::Timer) {
+        if tim.get_flag() {
+            tim.clr_flag();
+            if self.wraps_lo.fetch_add(1, Ordering::Release) == u32::MAX {
+                self.wraps_hi.fetch_add(1, Ordering::Release);
+            }
+        }
+    }
+
+    /* Get The Ticks Since The Start */
+    pub fn get_ticks(&self, tim: &timer::Timer) -> u64 {
+        loop {
+            let hi = self.wraps_hi.load(Ordering::Acquire);
+            let lo = self.wraps_lo.load(Ordering::Acquire);
+            let cnt = tim.get_cnt() as u64;
+            let pending = tim.get_flag();
+
+            if lo != self.wraps_lo.load(Ordering::Acquire) || hi != self.wraps_hi.load(Ordering::Acquire) {
+                continue;                           // The interrupt ran during the read
+            }
+
+            let wraps = ((hi as u64) << 32) | lo as u64;
+            let wraps = match pending && cnt < (self.range / 2) {
+                true    =>  wraps + 1,
+                false   =>  wraps
+            };
+            return wraps.wrapping_mul(self.range).wrapping_add(cnt);
+        }
+    }
+
+    /* Get The Current Time */
+    pub fn now(&self, tim: &timer::Timer) -> Instant {
+        let ns = (self.get_ticks(tim) as u128 * NS_PER_S) / self.tick_hz.max(1) as u128;
+        return Instant { ns: ns as u64 };
+    }
+
+    /* Get The Time Passed Since An Earlier Instant */
+    pub fn elapsed(&self, tim: &timer::Timer, since: Instant) -> Duration {
+        return self.now(tim).duration_since(since);
+    }
+
+    /* Get If The Deadline Has Passed */
+    pub fn expired(&self, tim: &timer::Timer, deadline: Instant) -> bool {
+        return self.now(tim) >= deadline;
+    }
+
+    /* Spin For At Least The Duration */
+    pub fn delay(&self, tim: &timer::Timer, time: Duration) {
+        let deadline = self.now(tim).saturating_add(time);
+
+        while !self.expired(tim, deadline) {
+            // SPIN HERE
+        }
+    }
+
+    /* Get The Counter Frequency In Hz */
+    pub fn get_tick_hz(&self) -> u32 {
+        return self.tick_hz;
+    }
+}
+
+impl Instant {
+    /* Instant At A Number Of Nanoseconds Since The Start */
+    pub fn from_nanos(ns: u64) -> Instant {
+        return Instant { ns };
+    }
+
+    pub fn as_nanos(&self) -> u64 {
+        return self.ns;
+    }
+
+    pub fn as_micros(&self) -> u64 {
+        return self.ns / 1_000;
+    }
+
+    pub fn as_millis(&self) -> u64 {
+        return self.ns / 1_000_000;
+    }
+
+    /* Time From An Earlier Instant, Zero If The Other Instant Is Later */
+    pub fn duration_since(&self, earlier: Instant) -> Duration {
+        return Duration::from_nanos(self.ns.saturating_sub(earlier.ns));
+    }
+
+    /* Instant A Duration Later, None On Overflow */
+    pub fn checked_add(&self, time: Duration) -> Option<Instant> {
+        if time.as_nanos() > u64::MAX as u128 {
+            return None;
+        }
+
+        return self.ns.checked_add(time.as_nanos() as u64).map(|ns| Instant { ns });
+    }
+
+    /* Instant A Duration Later, Held At The Last Instant On Overflow */
+    pub fn saturating_add(&self, time: Duration) -> Instant {
+        return match self.checked_add(time) {
+            Some(at)    =>  at,
+            None        =>  Instant { ns: u64::MAX }
+        };
+    }
+}
@@ -0,0 +1,285 @@
+/* 1-Wire Master (Bit Banged) */
+
+use super::{common, gpio};
+
+// The bus is a single open drain line with an external pull-up (4.7k typical).
+// The master drives the line low to start every time slot and releases it to let the pull-up or a slave set the level.
+// The pin must be configured as an open drain output before use, writing a '1' to the pin releases the line.
+// Time slots are timed by the delay source, interrupts that fire inside a slot stretch it and can corrupt the transfer,
+// mask them around transfers if the interrupt handlers are long.
+// Standard speed timing:
+// • Reset: low 480us, release, presence pulse sampled 70us later, slot ends 410us after that
+// • Write 1: low 6us, release 64us         • Write 0: low 60us, release 10us
+// • Read: low 6us, release, sample 9us later, slot ends 55us after that
+
+pub struct OneWire<'a, D: common::DelayUs> {
+    port:       &'a gpio::Gpio,     // Port Of The Bus Pin
+    pin:        u32,                // Pin Mask Of The Bus Pin
+    delay:      &'a D               // Microsecond Delay Source
+}
+
+/* ROM Search State, Kept Between Calls To Enumerate Every Device On The Bus */
+pub struct Search {
+    rom:                [u8; 8],    // Last ROM Found
+    last_discrepancy:   u32,        // Bit Position Of The Last Branch Taken Towards 0
+    done:               bool        // Last Device Found
+}
+
+/* ROM Commands */
+pub const SEARCH_ROM:   u8 = 0xF0;
+pub const READ_ROM:     u8 = 0x33;
+pub const MATCH_ROM:    u8 = 0x55;
+pub const SKIP_ROM:     u8 = 0xCC;
+
+/* Timing In Microseconds */
+const RESET_LOW:        u32 = 480;
+const RESET_PRESENCE:   u32 = 70;
+const RESET_END:        u32 = 410;
+const WRITE_1_LOW:      u32 = 6;
+const WRITE_1_END:      u32 = 64;
+const WRITE_0_LOW:      u32 = 60;
+const WRITE_0_END:      u32 = 10;
+const READ_LOW:         u32 = 6;
+const READ_SAMPLE:      u32 = 9;
+const READ_END:         u32 = 55;
+
+const ROM_BITS:         u32 = 64;
+const CRC8_POLY:        u8 = 0x8C;                      /* X^8 + X^5 + X^4 + 1, Reflected */
+
+/* Dallas/Maxim CRC8, A Buffer Ending In Its Own CRC Returns 0 */
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc: u8 = 0;
+
+    for byte in data.iter() {
+        let mut b = *byte;
+
+        for _ in 0..8 {
+            let mix = (crc ^ b) & 0x01;
+            crc >>= 1;
+            if mix != 0 {
+                crc ^= CRC8_POLY;
+            }
+            b >>= 1;
+        }
+    }
+    return crc;
+}
+
+impl<'a, D: common::DelayUs> OneWire<'a, D> {
+    /* Initialize The Structure, The Pin Is Released */
+    pub fn init(port: &'a gpio::Gpio, bit: u32, delay: &'a D) -> OneWire<'a, D> {
+        let ow = OneWire {
+            port,
+            pin:    1 << bit,
+            delay
+        };
+
+        ow.release();
+        return ow;
+    }
+
+    /* Reset The Bus, Returns True If At Least One Device Answered With A Presence Pulse */
+    pub fn reset(&self) -> bool {
+        self.drive_low();
+        self.delay.delay_us(RESET_LOW);
+        self.release();
+        self.delay.delay_us(RESET_PRESENCE);
+        let presence = !self.port.get_pin(self.pin);
+        self.delay.delay_us(RESET_END);
+
+        return presence;
+    }
+
+    pub fn write_bit(&self, bit: bool) {
+        self.drive_low();
+
+        if bit {
+            self.delay.delay_us(WRITE_1_LOW);
+            self.release();
+            self.delay.delay_us(WRITE_1_END);
+        } else {
+            self.delay.delay_us(WRITE_0_LOW);
+            self.release();
+            self.delay.delay_us(WRITE_0_END);
+        }
+    }
+
+    pub fn read_bit(&self) -> bool {
+        self.drive_low();
+        self.delay.delay_us(READ_LOW);
+        self.release();
+        self.delay.delay_us(READ_SAMPLE);
+        let bit = self.port.get_pin(self.pin);
+        self.delay.delay_us(READ_END);
+
+        return bit;
+    }
+
+    /* Bytes Are Sent Least Significant Bit First */
+    pub fn write_u8(&self, byte: u8) {
+        for i in 0..8 {
+            self.write_bit((byte >> i) & 0x01 != 0);
+        }
+    }
+
+    pub fn read_u8(&self) -> u8 {
+        let mut byte = 0;
+
+        for i in 0..8 {
+            if self.read_bit() {
+                byte |= 1 << i;
+            }
+        }
+        return byte;
+    }
+
+    pub fn write(&self, buf: &[u8]) {
+        for byte in buf.iter() {
+            self.write_u8(*byte);
+        }
+    }
+
+    pub fn read(&self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            *byte = self.read_u8();
+        }
+    }
+
+    /* Reset And Address One Device, Returns False If No Device Answered */
+    pub fn select(&self, rom: &[u8; 8]) -> bool {
+        if !self.reset() {
+            return false;
+        }
+
+        self.write_u8(MATCH_ROM);
+        self.write(rom);
+        return true;
+    }
+
+    /* Reset And Address Every Device, Returns False If No Device Answered */
+    pub fn skip(&self) -> bool {
+        if !self.reset() {
+            return false;
+        }
+
+        self.write_u8(SKIP_ROM);
+        return true;
+    }
+
+    /* Read The ROM Of The Only Device On The Bus, None If No Device Answered Or The CRC Failed */
+    pub fn read_rom(&self) -> Option<[u8; 8]> {
+        let mut rom = [0; 8];
+
+        if !self.reset() {
+            return None;
+        }
+
+        self.write_u8(READ_ROM);
+        self.read(&mut rom);
+
+        if crc8(&rom) != 0 {
+            return None;
+        }
+        return Some(rom);
+    }
+
+    // ROM search, one device is found per pass:
+    // For every bit of the ROM the devices still taking part send the bit and its complement.
+    // • 0 / 1 or 1 / 0: all remaining devices have the same bit, follow it.
+    // • 0 / 0: the devices differ at this bit (a discrepancy), take the 1 branch if the last pass branched lower down,
+    //   repeat the last pass choice before the last discrepancy, and take the 0 branch at new discrepancies.
+    // • 1 / 1: no device is taking part, the search failed.
+    // The master writes the chosen bit, devices with a different bit drop out until the next reset.
+    /* Find The Next Device, None When Every Device Has Been Found Or The Search Failed */
+    pub fn search(&self, state: &mut Search) -> Option<[u8; 8]> {
+        if state.done || !self.reset() {
+            return None;
+        }
+
+        let mut last_zero = 0;
+
+        self.write_u8(SEARCH_ROM);
+
+        for id in 1..=ROM_BITS {
+            let byte = ((id - 1) / 8) as usize;
+            let mask = 1 << ((id - 1) % 8);
+            let bit = self.read_bit();
+            let cmp = self.read_bit();
+
+            let dir = if bit && cmp {
+                state.reset();
+                return None;
+            } else if bit != cmp {
+                bit
+            } else {
+                let dir = if id < state.last_discrepancy {
+                    (state.rom[byte] & mask) != 0
+                } else {
+                    id == state.last_discrepancy
+                };
+
+                if !dir {
+                    last_zero = id;
+                }
+                dir
+            };
+
+            if dir {
+                state.rom[byte] |= mask;
+            } else {
+                state.rom[byte] &= !mask;
+            }
+            self.write_bit(dir);
+        }
+
+        state.last_discrepancy = last_zero;
+        state.done = last_zero == 0;
+
+        if crc8(&state.rom) != 0 {
+            state.reset();
+            return None;
+        }
+        return Some(state.rom);
+    }
+
+    /* Release The Line, The Pull-Up Takes It High */
+    fn release(&self) {
+        self.port.set_pin(self.pin);
+    }
+
+    fn drive_low(&self) {
+        self.port.clr_pin(self.pin);
+    }
+}
+
+impl Search {
+    pub fn init() -> Search {
+        return Search {
+            rom:                [0; 8],
+            last_discrepancy:   0,
+            done:               false
+        };
+    }
+
+    /* Restart The Search From The First Device */
+    pub fn reset(&mut self) {
+        self.rom = [0; 8];
+        self.last_discrepancy = 0;
+        self.done = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::crc8;
+
+    #[test]
+    fn crc8_vectors() {
+        assert_eq!(crc8(&[]), 0);
+        assert_eq!(crc8(&[0x02, 0x1C, 0xB8, 0x01, 0x00, 0x00, 0x00]), 0xA2);   // Maxim application note 27 ROM
+        assert_eq!(crc8(&[0x02, 0x1C, 0xB8, 0x01, 0x00, 0x00, 0x00, 0xA2]), 0);
+        assert_eq!(crc8(&[0x50, 0x05, 0x4B, 0x46, 0x7F, 0xFF, 0x0C, 0x10]), 0x1C); // DS18B20 power on scratchpad
+        assert_eq!(crc8(&[0; 9]), 0);                                          // All zero passes, callers must check more
+        assert_ne!(crc8(&[0x02, 0x1C, 0xB8, 0x01, 0x00, 0x00, 0x01, 0xA2]), 0);
+    }
+}
@@ -0,0 +1,78 @@
+/* Lock-Free Single Producer Single Consumer Byte Ring Buffer */
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+// One side only pushes (e.g. the receive interrupt) and the other only pops (e.g. the main loop), so no lock is needed:
+// • head is only written by the producer, after the byte is stored (Release), the consumer reads it with Acquire.
+// • tail is only written by the consumer, after the byte is taken, the producer reads it with Acquire.
+// One slot is always left empty to tell a full ring from an empty one, so the ring holds N - 1 bytes.
+
+pub struct Ring<const N: usize> {
+    buf:        UnsafeCell<[u8; N]>,
+    head:       AtomicUsize,        // Next Slot To Write
+    tail:       AtomicUsize         // Next Slot To Read
+}
+
+/* The Producer And Consumer Never Touch The Same Slot, See Above */
+unsafe impl<const N: usize> Sync for Ring<N> {}
+
+impl<const N: usize> Ring<N> {
+    /* Initialize An Empty Ring */
+    pub const fn init() -> Ring<N> {
+        return Ring {
+            buf:    UnsafeCell::new([0; N]),
+            head:   AtomicUsize::new(0),
+            tail:   AtomicUsize::new(0)
+        };
+    }
+
+    /* Add A Byte, Producer Only, Returns False If The Ring Is Full */
+    pub fn push(&self, byte: u8) -> bool {
+        if N == 0 {
+            return false;
+        }
+
+        let head = self.head.load(Ordering::Relaxed);
+        let next = (head + 1) % N;
+
+        if next == self.tail.load(Ordering::Acquire) {
+            return false;
+        }
+
+        unsafe { (*self.buf.get())[head] = byte; }
+        self.head.store(next, Ordering::Release);
+        return true;
+    }
+
+    /* Take The Oldest Byte, Consumer Only */
+    pub fn pop(&self) -> Option<u8> {
+        let tail = self.tail.load(Ordering::Relaxed);
+
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let byte = unsafe { (*self.buf.get())[tail] };
+        self.tail.store((tail + 1) % N, Ordering::Release);
+        return Some(byte);
+    }
+
+    /* Get The Number Of Bytes Held */
+    pub fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        return (head + N - tail) % N.max(1);
+    }
+
+    /* Get If No Byte Is Held */
+    pub fn is_empty(&self) -> bool {
+        return self.len() == 0;
+    }
+
+    /* Get The Number Of Bytes That Can Still Be Pushed */
+    pub fn free(&self) -> usize {
+        return N.saturating_sub(1) - self.len();
+    }
+}
@@ -0,0 +1,237 @@
+/* Inter-Integrated Circuit (I2C) Master (Bit Banged) */
+
+use super::{common, gpio, i2c};
+
+// SCL and SDA are open drain lines with external pull-ups, both pins must be configured as open drain outputs before use.
+// Writing a '1' to a pin releases the line, writing a '0' drives it low.
+// The master releases SCL and waits for it to read high before continuing, so a slave holding SCL low (clock stretching)
+// pauses the transfer, a slave that holds SCL longer than the stretch timeout aborts it.
+// The bit rate is an upper bound, each half period is a delay of at least 1us plus the time spent toggling the pins.
+// A 1us delay can end almost at once when it starts just before a tick, which would break the 1.3us SCL low time of Fm,
+// so Fm and Fm+ use a 2us half period and run at 250 kHz at most.
+// Frame:
+// • Start: SDA falls while SCL is high         • Stop: SDA rises while SCL is high
+// • Data: SDA changes while SCL is low, 8 bits most significant bit first, followed by an ACK bit (low = ACK) from the receiver
+// • 7-bit address: A6 - A0 R/W
+// • 10-bit address: 1 1 1 1 0 A9 A8 R/W then A7 - A0, a read is done by a restart and the first byte with R/W = 1
+
+pub struct SoftI2c<'a, D: common::DelayUs> {
+    scl_port:   &'a gpio::Gpio,     // Port Of The Clock Pin
+    scl:        u32,                // Pin Mask Of The Clock Pin
+    sda_port:   &'a gpio::Gpio,     // Port Of The Data Pin
+    sda:        u32,                // Pin Mask Of The Data Pin
+    delay:      &'a D,              // Microsecond Delay Source
+    half:       u32                 // Half Period Of The Clock In us
+}
+
+/* Half Period Of The Clock In us */
+const HALF_10KHZ:       u32 = 50;
+const HALF_100KHZ:      u32 = 5;
+const HALF_400KHZ:      u32 = 2;                        /* 1.3us tLOW Rounded Up With A Tick Of Margin */
+
+const STRETCH_TIMEOUT:  u32 = 10000;                    /* us A Slave May Hold SCL Low */
+
+/* Address */
+const ADDR_10_HEADER:   u32 = 0xF0;                     /* 1 1 1 1 0 A9 A8 R/W */
+const ADDR_10_HIGH:     u32 = 8;
+const ADDR_10_HIGH_MASK:u32 = common::MASK_2_BIT;
+const ADDR_7_MASK:      u32 = common::MASK_7_BIT;
+const ADDR_8_MASK:      u32 = common::MASK_8_BIT;
+const READ_BIT:         u8 = 0x01;
+
+impl<'a, D: common::DelayUs> SoftI2c<'a, D> {
+    /* Initialize The Structure, Both Lines Are Released */
+    pub fn init(scl_port: &'a gpio::Gpio, scl_bit: u32, sda_port: &'a gpio::Gpio, sda_bit: u32, delay: &'a D, mode: i2c::TimingMode) -> SoftI2c<'a, D> {
+        let i2c = SoftI2c {
+            scl_port,
+            scl:        1 << scl_bit,
+            sda_port,
+            sda:        1 << sda_bit,
+            delay,
+            half:       match mode {
+                i2c::TimingMode::Sm10KHz    => HALF_10KHZ,
+                i2c::TimingMode::Sm100KHz   => HALF_100KHZ,
+                i2c::TimingMode::Fm400KHz   => HALF_400KHZ,
+                i2c::TimingMode::FmPlus     => HALF_400KHZ      // Fm+ timing cannot be met, run as Fm
+            }
+        };
+
+        i2c.sda_port.set_pin(i2c.sda);
+        i2c.scl_port.set_pin(i2c.scl);
+        return i2c;
+    }
+
+    /* Start Or Restart Condition, Returns False If SCL Is Held Low Past The Timeout */
+    pub fn start(&self) -> bool {
+        self.sda_port.set_pin(self.sda);
+        if !self.scl_release() {
+            return false;
+        }
+        self.delay.delay_us(self.half);
+        self.sda_port.clr_pin(self.sda);
+        self.delay.delay_us(self.half);
+        self.scl_port.clr_pin(self.scl);
+        return true;
+    }
+
+    /* Stop Condition, Returns False If SCL Is Held Low Past The Timeout */
+    pub fn stop(&self) -> bool {
+        self.sda_port.clr_pin(self.sda);
+        self.delay.delay_us(self.half);
+        let ok = self.scl_release();
+        self.delay.delay_us(self.half);
+        self.sda_port.set_pin(self.sda);
+        self.delay.delay_us(self.half);
+        return ok;
+    }
+
+    /* Write A Byte, Returns True If The Slave Sent An ACK */
+    pub fn write_u8(&self, byte: u8) -> bool {
+        for i in (0..8).rev() {
+            if !self.write_bit((byte >> i) & 0x01 != 0) {
+                return false;
+            }
+        }
+
+        return self.read_bit() == Some(false);
+    }
+
+    /* Read A Byte, ACK Is Sent If More Bytes Follow, NACK On The Last Byte */
+    pub fn read_u8(&self, ack: bool) -> Option<u8> {
+        let mut byte = 0;
+
+        for _ in 0..8 {
+            byte = (byte << 1) | match self.read_bit() {
+                Some(bit)   => bit as u8,
+                None        => return None
+            };
+        }
+
+        if !self.write_bit(!ack) {
+            return None;
+        }
+        return Some(byte);
+    }
+
+    /* Write The Buffer, Returns False On A NACK Or A Stretch Timeout */
+    pub fn write(&self, buf: &[u8]) -> bool {
+        for byte in buf.iter() {
+            if !self.write_u8(*byte) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    /* Read The Buffer, Returns False On A Stretch Timeout */
+    pub fn read(&self, buf: &mut [u8]) -> bool {
+        let len = buf.len();
+
+        for (i, byte) in buf.iter_mut().enumerate() {
+            *byte = match self.read_u8(i + 1 < len) {
+                Some(b) => b,
+                None    => return false
+            };
+        }
+        return true;
+    }
+
+    /* Start And Send The Address, Returns True If The Slave Sent An ACK */
+    /* A 10-Bit Read Only Sends The Header, It Must Follow A 10-Bit Write To The Same Slave */
+    pub fn address(&self, slave_addr: u32, addr_10bit: bool, read: bool) -> bool {
+        let rw = if read { READ_BIT } else { 0 };
+
+        if !self.start() {
+            return false;
+        }
+
+        if addr_10bit {
+            let header = (ADDR_10_HEADER | (((slave_addr >> ADDR_10_HIGH) & ADDR_10_HIGH_MASK) << 1)) as u8;
+
+            if read {
+                return self.write_u8(header | rw);
+            }
+            return self.write_u8(header) && self.write_u8((slave_addr & ADDR_8_MASK) as u8);
+        }
+        return self.write_u8((((slave_addr & ADDR_7_MASK) << 1) as u8) | rw);
+    }
+
+    /* Write To The Slave, Returns False On A NACK Or A Stretch Timeout */
+    pub fn try_write(&self, slave_addr: u32, addr_10bit: bool, buf: &[u8]) -> bool {
+        let ok = self.address(slave_addr, addr_10bit, false) && self.write(buf);
+
+        return self.stop() && ok;
+    }
+
+    /* Write Then Restart And Read From The Slave, Returns False On A NACK Or A Stretch Timeout */
+    pub fn try_read(&self, slave_addr: u32, addr_10bit: bool, buf_write: &[u8], buf_read: &mut [u8]) -> bool {
+        let ok = self.address(slave_addr, addr_10bit, false) && self.write(buf_write)
+            && self.address(slave_addr, addr_10bit, true) && self.read(buf_read);
+
+        return self.stop() && ok;
+    }
+
+    /* Release SCL And Wait For The Slave To Release It */
+    fn scl_release(&self) -> bool {
+        let mut t = 0;
+
+        self.scl_port.set_pin(self.scl);
+
+        while !self.scl_port.get_pin(self.scl) {
+            if t > STRETCH_TIMEOUT {
+                return false;
+            }
+            self.delay.delay_us(1);
+            t+=1;
+        }
+        return true;
+    }
+
+    fn write_bit(&self, bit: bool) -> bool {
+        if bit {
+            self.sda_port.set_pin(self.sda);
+        } else {
+            self.sda_port.clr_pin(self.sda);
+        }
+
+        self.delay.delay_us(self.half);
+        if !self.scl_release() {
+            return false;
+        }
+        self.delay.delay_us(self.half);
+        self.scl_port.clr_pin(self.scl);
+        return true;
+    }
+
+    fn read_bit(&self) -> Option<bool> {
+        self.sda_port.set_pin(self.sda);
+        self.delay.delay_us(self.half);
+        if !self.scl_release() {
+            return None;
+        }
+        let bit = self.sda_port.get_pin(self.sda);
+        self.delay.delay_us(self.half);
+        self.scl_port.clr_pin(self.scl);
+        return Some(bit);
+    }
+}
+
+/* The 10-Bit Read Always Sends The Full Write Sequence First, So The Header Only Request Is Not Needed */
+impl<'a, D: common::DelayUs> i2c::I2cBus for SoftI2c<'a, D> {
+    fn std_write(&self, slave_addr: u32, addr_10bit: bool, _req_10bit: bool, buf: &[u8]) {
+        self.try_write(slave_addr, addr_10bit, buf);
+    }
+
+    fn std_read(&self, slave_addr: u32, addr_10bit: bool, _req_10bit: bool, buf_write: &[u8], buf_read: &mut [u8]) {
+        self.try_read(slave_addr, addr_10bit, buf_write, buf_read);
+    }
+
+    fn std_read_u8(&self, slave_addr: u32, addr_10bit: bool, _req_10bit: bool, byte_write: u8) -> u8 {
+        let mut byte = [0; 1];
+
+        if !self.try_read(slave_addr, addr_10bit, &[byte_write], &mut byte) {
+            return 0;
+        }
+        return byte[0];
+    }
+}
@@ -0,0 +1,141 @@
+/* Serial Peripheral Interface Master (Bit Banged) */
+
+use super::{common, gpio, spi};
+
+// SCK and MOSI must be configured as push pull outputs and MISO as an input before use, chip select is left to the caller.
+// CPOL sets the idle level of SCK, CPHA sets which edge the data is sampled on:
+// • CPHA = 0: MOSI is set before the first (leading) edge, MISO is sampled on the leading edge
+// • CPHA = 1: MOSI is set on the leading edge, MISO is sampled on the trailing edge
+// Transfers are full duplex 8-bit frames, the half period is a delay of at least 1us plus the time spent toggling the pins.
+
+pub struct SoftSpi<'a, D: common::DelayUs> {
+    sck_port:   &'a gpio::Gpio,     // Port Of The Clock Pin
+    sck:        u32,                // Pin Mask Of The Clock Pin
+    mosi_port:  &'a gpio::Gpio,     // Port Of The Master Out Pin
+    mosi:       u32,                // Pin Mask Of The Master Out Pin
+    miso_port:  &'a gpio::Gpio,     // Port Of The Master In Pin
+    miso:       u32,                // Pin Mask Of The Master In Pin
+    delay:      &'a D,              // Microsecond Delay Source
+    half:       u32,                // Half Period Of The Clock In us
+    cpol:       bool,               // Clock Idles High
+    cpha:       bool,               // Data Sampled On The Trailing Edge
+    lsb:        bool                // Least Significant Bit First
+}
+
+impl<'a, D: common::DelayUs> SoftSpi<'a, D> {
+    /* Initialize The Structure, Each Pin Is Given As (Port, Bit), SCK Is Set To Its Idle Level */
+    pub fn init(sck: (&'a gpio::Gpio, u32), mosi: (&'a gpio::Gpio, u32), miso: (&'a gpio::Gpio, u32),
+        delay: &'a D, half_us: u32, cs: spi::ClockSetup, bit: spi::BitFirst) -> SoftSpi<'a, D> {
+        let (cpha, cpol) = match cs {
+            spi::ClockSetup::RisingEdgeClockLow     => (false, false),
+            spi::ClockSetup::FallingEdgeClockLow    => (true, false),
+            spi::ClockSetup::RisingEdgeClockHigh    => (false, true),
+            spi::ClockSetup::FallingEdgeClockHigh   => (true, true)
+        };
+
+        let spi = SoftSpi {
+            sck_port:   sck.0,
+            sck:        1 << sck.1,
+            mosi_port:  mosi.0,
+            mosi:       1 << mosi.1,
+            miso_port:  miso.0,
+            miso:       1 << miso.1,
+            delay,
+            half:       half_us,
+            cpol,
+            cpha,
+            lsb:        match bit {
+                spi::BitFirst::Lsb  => true,
+                spi::BitFirst::Msb  => false
+            }
+        };
+
+        spi.set_sck(false);
+        return spi;
+    }
+
+    /* Send A Byte And Return The Byte Received At The Same Time */
+    pub fn transfer(&self, byte: u8) -> u8 {
+        let mut rx = 0;
+
+        for i in 0..8 {
+            let shift = if self.lsb { i } else { 7 - i };
+            let out = (byte >> shift) & 0x01 != 0;
+            let bit;
+
+            if self.cpha {
+                self.set_sck(true);
+                self.set_mosi(out);
+                self.delay.delay_us(self.half);
+                self.set_sck(false);
+                bit = self.miso_port.get_pin(self.miso);
+                self.delay.delay_us(self.half);
+            } else {
+                self.set_mosi(out);
+                self.delay.delay_us(self.half);
+                self.set_sck(true);
+                bit = self.miso_port.get_pin(self.miso);
+                self.delay.delay_us(self.half);
+                self.set_sck(false);
+            }
+
+            if bit {
+                rx |= 1 << shift;
+            }
+        }
+        return rx;
+    }
+
+    /* Send And Receive The Buffer In Place */
+    pub fn transfer_buf(&self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            *byte = self.transfer(*byte);
+        }
+    }
+
+    /* Drive SCK To Its Active (True) Or Idle (False) Level */
+    fn set_sck(&self, active: bool) {
+        if active != self.cpol {
+            self.sck_port.set_pin(self.sck);
+        } else {
+            self.sck_port.clr_pin(self.sck);
+        }
+    }
+
+    fn set_mosi(&self, bit: bool) {
+        if bit {
+            self.mosi_port.set_pin(self.mosi);
+        } else {
+            self.mosi_port.clr_pin(self.mosi);
+        }
+    }
+}
+
+/* Every Frame Is Clocked By The Call That Needs It, So The SpiBus Contract Holds Without A FIFO */
+impl<'a, D: common::DelayUs> spi::SpiBus for SoftSpi<'a, D> {
+    fn read(&self, buf: &mut [u8], len: usize) -> usize {
+        let n = if len > 0 && len < buf.len() { len } else { buf.len() };
+
+        for byte in buf[..n].iter_mut() {
+            *byte = self.transfer(spi::DUMMY);
+        }
+        return n;
+    }
+
+    fn write(&self, buf: &[u8]) -> u8 {
+        for byte in buf.iter() {
+            self.transfer(*byte);
+        }
+        return 0;
+    }
+
+    fn write_byte(&self, buf: u8) -> bool {
+        self.transfer(buf);
+        return true;
+    }
+
+    fn transfer(&self, buf: &mut [u8]) -> bool {
+        self.transfer_buf(buf);
+        return true;
+    }
+}
@@ -0,0 +1,347 @@
+/* Software Timers On One Compare Channel */
+
+use super::{monotonic, timer};
+
+// Any number of one-shot and periodic software timers share one capture/compare channel of the monotonic timebase timer.
+// Deadlines are absolute monotonic ticks kept in a queue sorted by deadline, the earliest is at the front.
+// Tickless: CCR is set to the low bits of the earliest deadline and the compare interrupt is only enabled while a timer is queued.
+// A deadline more than one counter range away makes the compare fire early, the handler finds nothing due and sets CCR again.
+// After CCR is written the time is read again, a deadline passed during the write is handled in the same call.
+// Periodic timers keep a fixed rate from their first deadline, periods missed while the interrupt was held off are skipped.
+// Callbacks only run from on_compare in the timer interrupt: once, every and cancel only set CCR, and if the earliest deadline
+// has already passed they raise the compare event by software so the interrupt runs it.
+// The service is shared between the interrupt and the main code, calls from the main code must mask the timer interrupt.
+
+pub struct SoftTimer<const N: usize> {
+    ch:         timer::Channel,     // Compare Channel Used For The Deadlines
+    queue:      Queue<N>            // Queued Timers
+}
+
+/* Deadline Queue, Sorted By Deadline, Earliest First */
+pub struct Queue<const N: usize> {
+    entries:    [Option<Entry>; N],
+    len:        usize,              // Queued Entries, Always At The Front
+    next_id:    u32                 // Id Of The Next Handle
+}
+
+/* Queued Timer */
+#[derive(Clone, Copy)]
+struct Entry {
+    handle:     Handle,
+    deadline:   u64,                // Monotonic Ticks
+    period:     u64,                // Ticks Between Runs, 0 = One-Shot
+    callback:   fn(Handle)
+}
+
+/* Timer Handle, Used To Cancel The Timer */
+#[derive(Clone, Copy, PartialEq)]
+pub struct Handle {
+    id:         u32
+}
+
+impl<const N: usize> Queue<N> {
+    /* Initialize An Empty Queue */
+    pub const fn init() -> Queue<N> {
+        return Queue {
+            entries:    [None; N],
+            len:        0,
+            next_id:    0
+        };
+    }
+
+    /* Queue A Timer At An Absolute Deadline, Period 0 For A One-Shot, None If The Queue Is Full */
+    pub fn insert(&mut self, deadline: u64, period: u64, callback: fn(Handle)) -> Option<Handle> {
+        if self.len == N {
+            return None;
+        }
+
+        let handle = Handle { id: self.next_id };
+        self.next_id = self.next_id.wrapping_add(1);
+        self.push(Entry { handle, deadline, period, callback });
+        return Some(handle);
+    }
+
+    /* Remove A Timer, Returns False If It Is Not Queued (Already Run Or Cancelled) */
+    pub fn cancel(&mut self, handle: Handle) -> bool {
+        let pos = match self.entries[..self.len].iter().position(|e| matches!(e, Some(e) if e.handle == handle)) {
+            Some(pos)   =>  pos,
+            None        =>  return false
+        };
+
+        self.remove(pos);
+        return true;
+    }
+
+    /* Get The Earliest Deadline */
+    pub fn next_deadline(&self) -> Option<u64> {
+        return match self.len {
+            0   =>  None,
+            _   =>  self.entries[0].map(|e| e.deadline)
+        };
+    }
+
+    /* Take The Earliest Timer Due At Now, A Periodic Timer Is Queued Again At Its Next Deadline After Now */
+    pub fn pop_due(&mut self, now: u64) -> Option<(Handle, fn(Handle))> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let entry = match self.entries[0] {
+            Some(entry) if entry.deadline <= now    =>  entry,
+            _                                       =>  return None
+        };
+
+        self.remove(0);
+
+        if let Some(missed) = (now - entry.deadline).checked_div(entry.period) {
+            let deadline = entry.deadline.saturating_add((missed + 1).saturating_mul(entry.period));
+            self.push(Entry { deadline, ..entry });                 // Periodic, a one-shot has period 0
+        }
+        return Some((entry.handle, entry.callback));
+    }
+
+    /* Get The Number Of Queued Timers */
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    /* Get If No Timer Is Queued */
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    /* Insert Behind Every Entry With The Same Or An Earlier Deadline, Timers Due Together Run In The Order Queued */
+    fn push(&mut self, entry: Entry) {
+        let mut i = self.len;
+
+        while i > 0 && matches!(self.entries[i - 1], Some(e) if e.deadline > entry.deadline) {
+            self.entries[i] = self.entries[i - 1];
+            i -= 1;
+        }
+
+        self.entries[i] = Some(entry);
+        self.len += 1;
+    }
+
+    fn remove(&mut self, pos: usize) {
+        for i in pos..(self.len - 1) {
+            self.entries[i] = self.entries[i + 1];
+        }
+
+        self.len -= 1;
+        self.entries[self.len] = None;
+    }
+}
+
+impl<const N: usize> SoftTimer<N> {
+    /* Initialize The Structure, The Channel Must Be Free On The Monotonic Timer */
+    pub const fn init(ch: timer::Channel) -> SoftTimer<N> {
+        return SoftTimer {
+            ch,
+            queue:  Queue::init()
+        };
+    }
+
+    /* Set Up The Compare Channel, Call After The Monotonic Timebase Is Started */
+    pub fn open(&self, tim: &timer::Timer) {
+        tim.clr_event_interrupt(timer::cc_event(self.ch));
+        tim.set_output_mode(self.ch, timer::OutputMode::Frozen);
+        tim.clr_event(timer::cc_event(self.ch));
+    }
+
+    /* Run The Callback Once After A Number Of Ticks, None If The Queue Is Full */
+    pub fn once(&mut self, mono: &monotonic::Monotonic, tim: &timer::Timer, ticks: u64, callback: fn(Handle)) -> Option<Handle> {
+        let deadline = mono.get_ticks(tim).saturating_add(ticks);
+        let handle = self.queue.insert(deadline, 0, callback);
+
+        self.arm(mono, tim);
+        return handle;
+    }
+
+    /* Run The Callback Every Period Of Ticks, First After One Period, None If The Queue Is Full Or The Period Is 0 */
+    pub fn every(&mut self, mono: &monotonic::Monotonic, tim: &timer::Timer, ticks: u64, callback: fn(Handle)) -> Option<Handle> {
+        if ticks == 0 {
+            return None;
+        }
+
+        let deadline = mono.get_ticks(tim).saturating_add(ticks);
+        let handle = self.queue.insert(deadline, ticks, callback);
+
+        self.arm(mono, tim);
+        return handle;
+    }
+
+    /* Cancel A Timer, Returns False If It Is Not Queued */
+    pub fn cancel(&mut self, mono: &monotonic::Monotonic, tim: &timer::Timer, handle: Handle) -> bool {
+        let found = self.queue.cancel(handle);
+
+        self.arm(mono, tim);
+        return found;
+    }
+
+    /* Run The Due Callbacks And Set The Next Deadline, Call From The Timer Interrupt */
+    pub fn on_compare(&mut self, mono: &monotonic::Monotonic, tim: &timer::Timer) {
+        let event = timer::cc_event(self.ch);
+
+        if !tim.get_event(event) {
+            return;
+        }
+
+        tim.clr_event(event);
+        self.reload(mono, tim);
+    }
+
+    /* Get The Number Of Queued Timers */
+    pub fn len(&self) -> usize {
+        return self.queue.len();
+    }
+
+    /* Get If No Timer Is Queued */
+    pub fn is_empty(&self) -> bool {
+        return self.queue.is_empty();
+    }
+
+    /* Set CCR To The Earliest Deadline Without Running Callbacks, A Deadline Already Passed Raises The Compare Event */
+    fn arm(&self, mono: &monotonic::Monotonic, tim: &timer::Timer) {
+        let event = timer::cc_event(self.ch);
+
+        let deadline = match self.queue.next_deadline() {
+            Some(deadline)  =>  deadline,
+            None            =>  {
+                tim.clr_event_interrupt(event);
+                return;
+            }
+        };
+
+        tim.set_ccr(self.ch, deadline as u32);              // Low bits, 16-bit timers ignore the upper half
+        tim.set_event_interrupt(event);
+
+        if mono.get_ticks(tim) >= deadline {
+            tim.set_cc_event(self.ch);                      // Runs once the caller unmasks the timer interrupt
+        }
+    }
+
+    /* Run Everything Due, Then Set CCR To The Next Deadline Until It Is Still Ahead After The Write */
+    fn reload(&mut self, mono: &monotonic::Monotonic, tim: &timer::Timer) {
+        let event = timer::cc_event(self.ch);
+
+        loop {
+            while let Some((handle, callback)) = self.queue.pop_due(mono.get_ticks(tim)) {
+                callback(handle);
+            }
+
+            let deadline = match self.queue.next_deadline() {
+                Some(deadline)  =>  deadline,
+                None            =>  {
+                    tim.clr_event_interrupt(event);
+                    return;
+                }
+            };
+
+            tim.set_ccr(self.ch, deadline as u32);          // Low bits, 16-bit timers ignore the upper half
+            tim.set_event_interrupt(event);
+
+            if mono.get_ticks(tim) < deadline {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Handle, Queue};
+
+    fn a(_: Handle) {}
+    fn b(_: Handle) {}
+
+    #[test]
+    fn ordered_by_deadline() {
+        let mut q: Queue<4> = Queue::init();
+        let late = q.insert(300, 0, a).unwrap();
+        let first = q.insert(100, 0, a).unwrap();
+        let second = q.insert(100, 0, b).unwrap();              // Same deadline, runs after the one queued first
+        let mid = q.insert(200, 0, b).unwrap();
+
+        assert!(q.insert(1, 0, a).is_none());                   // Full
+        assert_eq!(q.next_deadline(), Some(100));
+        assert!(q.pop_due(99).is_none());
+
+        for h in [first, second, mid, late] {
+            assert!(q.pop_due(1000).unwrap().0 == h);
+        }
+        assert!(q.is_empty());
+        assert_eq!(q.next_deadline(), None);
+    }
+
+    #[test]
+    fn cancel() {
+        let mut q: Queue<4> = Queue::init();
+        let h1 = q.insert(100, 0, a).unwrap();
+        let h2 = q.insert(200, 0, a).unwrap();
+        let h3 = q.insert(300, 0, a).unwrap();
+
+        assert!(q.cancel(h1));
+        assert!(!q.cancel(h1));                                 // Already cancelled
+        assert_eq!(q.next_deadline(), Some(200));
+        assert!(q.cancel(h3));
+        assert_eq!(q.len(), 1);
+        assert!(q.pop_due(200).unwrap().0 == h2);
+        assert!(!q.cancel(h2));                                 // Already run
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn periodic_rearm() {
+        let mut q: Queue<2> = Queue::init();
+        let h = q.insert(50, 30, a).unwrap();
+        let once = q.insert(70, 0, b).unwrap();
+
+        assert!(q.pop_due(50).unwrap().0 == h);
+        assert_eq!(q.next_deadline(), Some(70));
+        assert!(q.pop_due(70).unwrap().0 == once);
+        assert_eq!(q.next_deadline(), Some(80));                // Fixed rate from the first deadline
+
+        assert!(q.pop_due(145).unwrap().0 == h);                // Late by two periods, the missed runs are skipped
+        assert_eq!(q.next_deadline(), Some(170));
+        assert!(q.pop_due(145).is_none());
+        assert_eq!(q.len(), 1);
+
+        assert!(q.cancel(h));
+        assert!(q.is_empty());
+    }
+
+    #[test]
+    fn counter_wrap() {
+        let mut q: Queue<3> = Queue::init();
+        let wrap16 = 0x1_0000 + 5;                              // Low 16 bits below the deadline before it
+        let wrap32 = 0x1_0000_0000 + 5;
+
+        let h32 = q.insert(wrap32, 0, a).unwrap();
+        let h16 = q.insert(wrap16, 0, a).unwrap();
+        let h = q.insert(0xFFF0, 0, a).unwrap();
+
+        assert!(q.pop_due(0xFFFF).unwrap().0 == h);
+        assert!(q.pop_due(0xFFFF).is_none());
+        assert!(q.pop_due(wrap16).unwrap().0 == h16);
+        assert!(q.pop_due(0xFFFF_FFFF).is_none());
+        assert!(q.pop_due(wrap32).unwrap().0 == h32);
+
+        let p = q.insert(u64::MAX - 10, 100, b).unwrap();       // Next deadline saturates instead of wrapping to the front
+        assert!(q.pop_due(u64::MAX - 10).unwrap().0 == p);
+        assert_eq!(q.next_deadline(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn handle_id_wrap() {
+        let mut q: Queue<2> = Queue::init();
+
+        q.next_id = u32::MAX;
+        let last = q.insert(10, 0, a).unwrap();
+        let first = q.insert(20, 0, a).unwrap();
+
+        assert!(last != first);
+        assert!(q.cancel(first));
+        assert!(q.pop_due(10).unwrap().0 == last);
+    }
+}
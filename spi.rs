@@ -128,7 +128,21 @@ const FRLVL_OFFSET:     u32 = 9;
 const FTLVL_OFFSET:     u32 = 11;
 
 const TIMEOUT:          u32 = 1600;
-
+pub const DUMMY:        u8 = 0xFF;                      /* Sent By SpiBus::read While Receiving */
+
+
+/* Transfer Level API, Implemented By The Hardware And The Bit Banged Master So Device Drivers Work On Either */
+// Every call clocks its own frames and only returns the bytes received during those frames:
+// • read clocks out DUMMY for each byte and keeps what comes back, returns the bytes read or 0 on timeout
+// • write and write_byte clock out the data and discard what comes back
+// • transfer clocks out the buffer and replaces it with what comes back, returns false on timeout
+// Bytes received during an earlier call are never returned.
+pub trait SpiBus {
+    fn read(&self, buf: &mut [u8], len: usize) -> usize;
+    fn write(&self, buf: &[u8]) -> u8;
+    fn write_byte(&self, buf: u8) -> bool;
+    fn transfer(&self, buf: &mut [u8]) -> bool;
+}
 
 impl Spi {
     /* Initialize The Structure */
@@ -301,6 +315,38 @@ impl Spi {
         return true;
     }
 
+    /* Send And Receive The Buffer In Place, Returns False On Timeout */
+    pub fn transfer(&self, buf: &mut [u8]) -> bool {
+        for byte in buf.iter_mut() {
+            match self.transfer_byte(*byte) {
+                Some(rx) => *byte = rx,
+                None => return false
+            }
+        }
+        return true;
+    }
+
+    /* Send One Byte And Wait For The Byte Received With It, Stale Received Data Is Dropped First */
+    fn transfer_byte(&self, buf: u8) -> Option<u8> {
+        let mut i = 0;
+
+        while pointer::get_ptr_vol_u32(self.sr, FRLVL_OFFSET, FRLVL_MASK) != 0 {
+            pointer::get_ptr_vol_raw_u8(self.dr);
+        }
+
+        if !self.write_byte(buf) {
+            return None;
+        }
+
+        while !pointer::get_ptr_vol_bit_u32(self.sr, RXNE_BIT) {
+            if i > TIMEOUT {
+                return None;
+            }
+            i+=1;
+        }
+        return Some(pointer::get_ptr_vol_raw_u8(self.dr));
+    }
+
     //  The correct disable procedure is (except when receive only mode is used):
     //      1. Wait until FTLVL[1:0] = 00 (no more data to transmit).
     //      2. Wait until BSY=0 (the last data frame is processed).
@@ -354,3 +400,33 @@ impl Spi {
         }
     }
 }
+
+/* Every Byte Waits For Its Own Received Byte, Unlike The Inherent read / write Which Work On The FIFOs Directly */
+impl SpiBus for Spi {
+    fn read(&self, buf: &mut [u8], len: usize) -> usize {
+        let n = if len > 0 && len < buf.len() { len } else { buf.len() };
+
+        buf[..n].fill(DUMMY);
+        return match Spi::transfer(self, &mut buf[..n]) {
+            true => n,
+            false => 0
+        };
+    }
+
+    fn write(&self, buf: &[u8]) -> u8 {
+        for byte in buf.iter() {
+            if self.transfer_byte(*byte).is_none() {
+                return 1;
+            }
+        }
+        return 0;
+    }
+
+    fn write_byte(&self, buf: u8) -> bool {
+        return self.transfer_byte(buf).is_some();
+    }
+
+    fn transfer(&self, buf: &mut [u8]) -> bool {
+        return Spi::transfer(self, buf);
+    }
+}
@@ -0,0 +1,55 @@
+/* System Configuration Controller (SYSCFG) */
+
+use super::{common, gpio, pointer};
+
+pub struct Syscfg {
+    exticr1:    *mut u32,       // External Interrupt Configuration Register 1
+    exticr2:    *mut u32,       // External Interrupt Configuration Register 2
+    exticr3:    *mut u32,       // External Interrupt Configuration Register 3
+    exticr4:    *mut u32        // External Interrupt Configuration Register 4
+}
+
+/* Register Offset */
+const EXTICR1:  u32 = 0x08;
+const EXTICR2:  u32 = 0x0C;
+const EXTICR3:  u32 = 0x10;
+const EXTICR4:  u32 = 0x14;
+
+/* Register Masks */
+/* EXTICR */
+const EXTI_MASK:        u32 = common::MASK_3_BIT;       /* EXTIx is three bits wide, 111 */
+
+/* Register Offsets */
+/* EXTICR */
+const EXTI_OFFSET:      u32 = 4;                        /* EXTIx fields are four bits apart, four lines per register */
+const EXTI_PER_REG:     u32 = 4;
+
+const EXTI_LINES:       u32 = 16;
+
+impl Syscfg {
+    /* Initialize The Structure */
+    pub fn init(base: u32) -> Syscfg {
+        return Syscfg {
+            exticr1:    (base + EXTICR1)    as *mut u32,
+            exticr2:    (base + EXTICR2)    as *mut u32,
+            exticr3:    (base + EXTICR3)    as *mut u32,
+            exticr4:    (base + EXTICR4)    as *mut u32
+        };
+    }
+
+    /* Route The GPIO Port To The EXTI Line, The SYSCFG Clock Must Be Enabled In RCC APB2ENR */
+    pub fn set_exti(&self, line: u32, port: gpio::Port) {
+        if line >= EXTI_LINES {
+            return;
+        }
+
+        let exticr = match line / EXTI_PER_REG {
+            0 => self.exticr1,
+            1 => self.exticr2,
+            2 => self.exticr3,
+            _ => self.exticr4
+        };
+
+        pointer::set_ptr_vol_u32(exticr, (line % EXTI_PER_REG) * EXTI_OFFSET, EXTI_MASK, port as u32);
+    }
+}
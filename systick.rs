@@ -0,0 +1,169 @@
+/* SysTick Timer */
+
+use core::sync::atomic::{AtomicU32, Ordering};
+use super::{common, pointer};
+
+// SysTick is a 24-bit down counter in the core, clocked by the processor clock (CLKSOURCE = 1).
+// It counts from RVR down to 0, reloads RVR on the next clock and sets COUNTFLAG, so the period is RVR + 1 clocks.
+// The millisecond tick uses RVR = core clock / 1000 - 1:
+// • Interrupt mode: TICKINT raises the SysTick exception every millisecond, the handler calls on_tick.
+// • Polling mode: no exception, poll reads COUNTFLAG (cleared by the read) and counts the tick, it must be called at least once a millisecond.
+// The delays count the clocks passed on CVR, handling the reload wrap, so they work in both modes and with the counter running freely.
+
+pub struct Systick {
+    csr:        *mut u32,       // Control And Status Register
+    rvr:        *mut u32,       // Reload Value Register
+    cvr:        *mut u32,       // Current Value Register
+    calib:      *mut u32,       // Calibration Value Register
+    core_hz:    u32             // Processor Clock
+}
+
+/* Register Offset */
+const CSR:      u32 = 0x00;
+const RVR:      u32 = 0x04;
+const CVR:      u32 = 0x08;
+const CALIB:    u32 = 0x0C;
+
+/* Enumerations */
+// Millisecond Tick Source, Interrupt: SysTick exception every tick     Polling: COUNTFLAG read by poll
+pub enum Mode {
+    Interrupt,
+    Polling
+}
+
+/* Register Masks */
+/* RVR / CVR */
+const COUNT_MASK:       u32 = 0x00FFFFFF;
+/* CALIB */
+const TENMS_MASK:       u32 = 0x00FFFFFF;
+
+/* Register Bits */
+/* CSR */
+const ENABLE_BIT:       u32 = common::BIT_0;            /* Counter Enable */
+const TICKINT_BIT:      u32 = common::BIT_1;            /* Exception On Reaching 0 */
+const CLKSOURCE_BIT:    u32 = common::BIT_2;            /* 0 = Processor Clock / 8, 1 = Processor Clock */
+const COUNTFLAG_BIT:    u32 = common::BIT_16;           /* Counted To 0 Since The Last Read */
+
+const MS_PER_S:         u32 = 1_000;
+const US_PER_S:         u64 = 1_000_000;
+
+/* Milliseconds Counted, Shared With The SysTick Exception */
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+/* Count A Millisecond, Call From The SysTick Exception Handler */
+pub fn on_tick() {
+    TICKS.fetch_add(1, Ordering::Relaxed);
+}
+
+impl Systick {
+    /* Initialize The Structure, Core Clock In Hz */
+    pub fn init(base: u32, core_hz: u32) -> Systick {
+        return Systick {
+            csr:        (base + CSR)    as *mut u32,
+            rvr:        (base + RVR)    as *mut u32,
+            cvr:        (base + CVR)    as *mut u32,
+            calib:      (base + CALIB)  as *mut u32,
+            core_hz
+        };
+    }
+
+    /* Start The Millisecond Tick, Returns False If A Millisecond Does Not Fit The 24-Bit Counter At The Core Clock */
+    pub fn open(&self, mode: Mode) -> bool {
+        let reload = self.core_hz / MS_PER_S;
+
+        if reload == 0 || (reload - 1) > COUNT_MASK {
+            return false;
+        }
+
+        pointer::set_ptr_vol_raw_u32(self.csr, 0);
+        pointer::set_ptr_vol_raw_u32(self.rvr, reload - 1);
+        pointer::set_ptr_vol_raw_u32(self.cvr, 0);                  // Any write clears CVR and COUNTFLAG
+        TICKS.store(0, Ordering::Relaxed);
+
+        let tickint = match mode {
+            Mode::Interrupt =>  TICKINT_BIT,
+            Mode::Polling   =>  0
+        };
+        pointer::set_ptr_vol_raw_u32(self.csr, CLKSOURCE_BIT | tickint | ENABLE_BIT);
+        return true;
+    }
+
+    /* Stop The Counter */
+    pub fn stop(&self) {
+        pointer::set_ptr_vol_raw_u32(self.csr, 0);
+    }
+
+    /* Count The Tick If The Counter Reached 0, Call At Least Once A Millisecond In Polling Mode, Returns True On A Tick */
+    pub fn poll(&self) -> bool {
+        if (pointer::get_ptr_vol_raw_u32(self.csr) & COUNTFLAG_BIT) == 0 {
+            return false;
+        }
+
+        on_tick();
+        return true;
+    }
+
+    /* Get The Milliseconds Since open, Wraps After 49.7 Days */
+    pub fn get_ms(&self) -> u32 {
+        return TICKS.load(Ordering::Relaxed);
+    }
+
+    /* Get The Current Counter Value */
+    pub fn get_cnt(&self) -> u32 {
+        return pointer::get_ptr_vol_raw_u32(self.cvr) & COUNT_MASK;
+    }
+
+    /* Get The Calibration Reload For 10 ms, 0 If Not Given */
+    pub fn get_calib(&self) -> u32 {
+        return pointer::get_ptr_vol_raw_u32(self.calib) & TENMS_MASK;
+    }
+
+    /* Spin For A Number Of Core Clocks, Returns At Once If The Counter Is Stopped, In Polling Mode The Ticks Are Counted While Waiting */
+    pub fn delay_cycles(&self, cycles: u64) {
+        let csr = pointer::get_ptr_vol_raw_u32(self.csr);
+        let polling = (csr & TICKINT_BIT) == 0;
+
+        if (csr & ENABLE_BIT) == 0 {
+            return;                                                 // Would never finish
+        }
+
+        if polling && (csr & COUNTFLAG_BIT) != 0 {
+            on_tick();                                              // Reading CSR cleared the flag
+        }
+
+        let range = (pointer::get_ptr_vol_raw_u32(self.rvr) & COUNT_MASK) as u64 + 1;
+        let mut last = self.get_cnt() as u64;
+        let mut passed: u64 = 0;
+
+        while passed < cycles {
+            let now = self.get_cnt() as u64;
+
+            passed += match now <= last {
+                true    =>  last - now,
+                false   =>  last + range - now                      // Reloaded since the last read
+            };
+            last = now;
+
+            if polling {
+                self.poll();
+            }
+        }
+    }
+
+    /* Spin For A Number Of Microseconds */
+    pub fn delay_us(&self, us: u32) {
+        self.delay_cycles((us as u64 * self.core_hz as u64) / US_PER_S);
+    }
+
+    /* Spin For A Number Of Milliseconds */
+    pub fn delay_ms(&self, ms: u32) {
+        self.delay_cycles((ms as u64 * self.core_hz as u64) / MS_PER_S as u64);
+    }
+}
+
+/* Microsecond Delay For The Bit Banged Drivers */
+impl common::DelayUs for Systick {
+    fn delay_us(&self, us: u32) {
+        Systick::delay_us(self, us);
+    }
+}
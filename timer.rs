@@ -21,6 +21,7 @@ pub struct Timer {
     ccr2:       *mut u32,       // Capture/Compare Register 2
     ccr3:       *mut u32,       // Capture/Compare Register 3
     ccr4:       *mut u32,       // Capture/Compare Register 4
+    bdtr:       *mut u32,       // Break And Dead-Time Register (Advanced Timers Only)
     dcr:        *mut u32,       // DMA Control Register
     dmar:       *mut u32,       // DMA Address Register
     or:         *mut u32,       // Option Register
@@ -44,6 +45,7 @@ const CCR1:     u32 = 0x34;
 const CCR2:     u32 = 0x38;
 const CCR3:     u32 = 0x3C;
 const CCR4:     u32 = 0x40;
+const BDTR:     u32 = 0x44;
 const DCR:      u32 = 0x48;
 const DMAR:     u32 = 0x4C;
 const OR:       u32 = 0x50;
@@ -61,14 +63,239 @@ pub enum Direction {
     Downcount
 }
 
+/* Capture/Compare Channel */
+#[derive(Clone, Copy, PartialEq)]
+pub enum Channel {
+    Ch1,
+    Ch2,
+    Ch3,
+    Ch4
+}
+
+// Input Capture Mapping, 01: ICx is mapped on TIx     10: ICx is mapped on the other TI of the pair (IC1 on TI2)     11: ICx is mapped on TRC
+pub enum CaptureInput {
+    Direct      = 1,
+    Indirect    = 2,
+    Trc         = 3
+}
+
+// Input Capture Edge, CCxNP CCxP 00: Rising edge     01: Falling edge     11: Both edges
+pub enum Edge {
+    Rising,
+    Falling,
+    Both
+}
+
+// Input Capture Prescaler, 00: Capture every event     01: Every 2 events     10: Every 4 events     11: Every 8 events
+pub enum CapturePsc {
+    Div1,
+    Div2,
+    Div4,
+    Div8
+}
+
+// Encoder Mode, SMS 001: Counts on TI2 edges     010: Counts on TI1 edges     011: Counts on both TI1 and TI2 edges (x4)
+pub enum EncoderMode {
+    Mode1       = 1,
+    Mode2       = 2,
+    Mode3       = 3
+}
+
+// Output Compare Mode Used For PWM, 0110: PWM mode 1, active while CNT < CCR     0111: PWM mode 2, inactive while CNT < CCR
+pub enum PwmMode {
+    Mode1       = 6,
+    Mode2       = 7
+}
+
+// Output Compare Mode, OCxM[3:0] 0000: Frozen     0001: Active on match     0010: Inactive on match     0011: Toggle on match
+// 0100: Forced inactive     0101: Forced active     0110: PWM mode 1     0111: PWM mode 2
+// 1000: Retriggerable OPM mode 1     1001: Retriggerable OPM mode 2     1100: Combined PWM mode 1     1101: Combined PWM mode 2
+// 1110: Asymmetric PWM mode 1     1111: Asymmetric PWM mode 2
+pub enum OutputMode {
+    Frozen          = 0,
+    ActiveOnMatch   = 1,
+    InactiveOnMatch = 2,
+    Toggle          = 3,
+    ForceInactive   = 4,
+    ForceActive     = 5,
+    Pwm1            = 6,
+    Pwm2            = 7,
+    RetrigOpm1      = 8,
+    RetrigOpm2      = 9,
+    CombinedPwm1    = 12,
+    CombinedPwm2    = 13,
+    AsymmetricPwm1  = 14,
+    AsymmetricPwm2  = 15
+}
+
+// Output Polarity, CCxP 0: OCx active high     1: OCx active low
+pub enum Polarity {
+    ActiveHigh,
+    ActiveLow
+}
+
+// Counter Alignment, CMS 00: Edge aligned     01: Center aligned, compare flags set counting down
+// 10: Center aligned, compare flags set counting up     11: Center aligned, compare flags set both ways
+#[derive(Clone, Copy)]
+pub enum Align {
+    Edge        = 0,
+    CenterDown  = 1,
+    CenterUp    = 2,
+    CenterBoth  = 3
+}
+
+// Counter Width, TIM2 And TIM5 Are 32-Bit, All Others 16-Bit
+#[derive(Clone, Copy)]
+pub enum Width {
+    Bits16,
+    Bits32
+}
+
+/* Period Solver Result */
+pub struct Scale {
+    psc:        u32,            // Prescaler Register Value
+    arr:        u32,            // Auto Reload Register Value
+    period_ns:  u64,            // Achieved Period In Nanoseconds
+    error_ns:   i64             // Achieved Period - Requested Period In Nanoseconds
+}
+
+/* Period Solver Errors */
+#[derive(Debug, PartialEq)]
+pub enum ScaleError {
+    Zero,                       // Period Or Frequency Of Zero Requested
+    TooShort,                   // Less Than 2 Timer Clocks
+    TooLong                     // More Than 65536 x (Max ARR + 1) Timer Clocks
+}
+
+// Timer Event, The Value Is The Flag Position In SR, Interrupt Enables In DIER Share The Position Of Their Flag
+// Break 2 Is Enabled With BIE, An Overcapture Is Reported Through The Interrupt Of Its Channel
+#[derive(Clone, Copy, PartialEq)]
+pub enum Event {
+    Update      = 0,
+    Cc1         = 1,
+    Cc2         = 2,
+    Cc3         = 3,
+    Cc4         = 4,
+    Com         = 5,
+    Trigger     = 6,
+    Break       = 7,
+    Break2      = 8,
+    Cc1Over     = 9,
+    Cc2Over     = 10,
+    Cc3Over     = 11,
+    Cc4Over     = 12
+}
+
+// Trigger Output, MMS 000: Reset (UG)     001: Enable (CEN)     010: Update event     011: Compare pulse (CC1IF set)
+// 100: OC1REF     101: OC2REF     110: OC3REF     111: OC4REF
+pub enum MasterMode {
+    Reset       = 0,
+    Enable      = 1,
+    Update      = 2,
+    Compare     = 3,
+    Oc1Ref      = 4,
+    Oc2Ref      = 5,
+    Oc3Ref      = 6,
+    Oc4Ref      = 7
+}
+
+// Slave Mode, SMS 0000: Disabled     0100: Reset mode     0101: Gated mode     0110: Trigger mode     0111: External clock mode 1
+// 1000: Combined reset + trigger mode
+// External clock mode 2 is ECE = 1 in SMCR, the counter is clocked by ETRF and the trigger input is not used
+pub enum SlaveMode {
+    Disabled,
+    Reset,
+    Gated,
+    Trigger,
+    ExtClock1,
+    ExtClock2,
+    ResetTrigger
+}
+
+// Trigger Input, TS 000: ITR0     001: ITR1     010: ITR2     011: ITR3     100: TI1F_ED     101: TI1FP1     110: TI2FP2     111: ETRF
+// The timer behind each ITRx depends on the slave timer, see the internal trigger connection table of the timer
+#[derive(Clone, Copy)]
+pub enum Trigger {
+    Itr0        = 0,
+    Itr1        = 1,
+    Itr2        = 2,
+    Itr3        = 3,
+    Ti1fEd      = 4,
+    Ti1fp1      = 5,
+    Ti2fp2      = 6,
+    Etrf        = 7
+}
+
+// External Trigger Prescaler, ETPS 00: Off     01: ETRP / 2     10: ETRP / 4     11: ETRP / 8
+pub enum EtrPsc {
+    Div1,
+    Div2,
+    Div4,
+    Div8
+}
+
+// DMA Burst Base Register, DBA Is The Register Offset From CR1 In Words, 00000: CR1     00001: CR2 ... 10001: BDTR
+#[derive(Clone, Copy)]
+pub enum BurstReg {
+    Cr1         = 0,
+    Cr2         = 1,
+    Smcr        = 2,
+    Dier        = 3,
+    Sr          = 4,
+    Egr         = 5,
+    Ccmr1       = 6,
+    Ccmr2       = 7,
+    Ccer        = 8,
+    Cnt         = 9,
+    Psc         = 10,
+    Arr         = 11,
+    Rcr         = 12,
+    Ccr1        = 13,
+    Ccr2        = 14,
+    Ccr3        = 15,
+    Ccr4        = 16,
+    Bdtr        = 17
+}
+
+// Break Input Polarity, BKP 0: Break input active low     1: Break input active high
+pub enum BreakPolarity {
+    ActiveLow,
+    ActiveHigh
+}
+
 /* Register Masks */
 /* CR1 */
 const CMS_MASK:         u32 = common::MASK_2_BIT;       /* Mode is mask required, here we set the mask to two bit 11 */
 const CKD_MASK:         u32 = common::MASK_2_BIT;       /* Mode is mask required, here we set the mask to two bit 11 */
 
+/* CR2 */
+const MMS_MASK:         u32 = common::MASK_3_BIT;
+
+/* SMCR */
+const SMS_MASK:         u32 = common::MASK_3_BIT;
+const TS_MASK:          u32 = common::MASK_3_BIT;
+const ETF_MASK:         u32 = common::MASK_4_BIT;
+const ETPS_MASK:        u32 = common::MASK_2_BIT;
+
+/* RCR */
+const REP_MASK:         u32 = common::MASK_16_BIT;      /* 16 Bits On TIM1 And TIM8, 8 Bits On TIM15 And TIM16 */
+
 /* CCMR */
 const CCS_MASK:         u32 = common::MASK_2_BIT;
 const OCM_MASK:         u32 = common::MASK_3_BIT;
+const ICPSC_MASK:       u32 = common::MASK_2_BIT;
+const ICF_MASK:         u32 = common::MASK_4_BIT;
+
+/* BDTR */
+const DTG_MASK:         u32 = common::MASK_8_BIT;
+const BKF_MASK:         u32 = common::MASK_4_BIT;
+
+/* DCR */
+const DBA_MASK:         u32 = common::MASK_5_BIT;
+const DBL_MASK:         u32 = common::MASK_5_BIT;
+
+/* CCER */
+const CCP_MASK:         u32 = common::BIT_1 | common::BIT_3;   /* CCxP And CCxNP Of Channel 1, Shift By CCER_SHIFT Per Channel */
 
 /* Register Bits */
 /* CR1 */
@@ -80,12 +307,46 @@ const DIR_BIT:          u32 = common::BIT_4;            /* 0 = Upcounter, 1 = Do
 const ARPE_BIT:         u32 = common::BIT_7;            /* 0 ARR = Not Buffered, 1 = Buffered */
 const UIFREMAP_BIT:     u32 = common::BIT_11;           /* Output, 0 = Pulse, 1 = Toggle */
 
+/* BDTR */
+const OSSI_BIT:         u32 = common::BIT_10;           /* Off State Selection For Idle Mode */
+const OSSR_BIT:         u32 = common::BIT_11;           /* Off State Selection For Run Mode */
+const BKE_BIT:          u32 = common::BIT_12;           /* Break Enable */
+const BKP_BIT:          u32 = common::BIT_13;           /* Break Polarity */
+const AOE_BIT:          u32 = common::BIT_14;           /* Automatic Output Enable, MOE Is Set Again On The Next Update Event After A Break */
+const MOE_BIT:          u32 = common::BIT_15;           /* Main Output Enable, Cleared By Hardware On A Break */
+const BK2E_BIT:         u32 = common::BIT_24;           /* Break 2 Enable */
+const BK2P_BIT:         u32 = common::BIT_25;           /* Break 2 Polarity */
+
+/* SMCR */
+const MSM_BIT:          u32 = common::BIT_7;            /* Master/Slave Mode, 1 = Trigger Input Delayed To Synchronize The Slaves With The Master */
+const ECE_BIT:          u32 = common::BIT_14;           /* External Clock Mode 2 Enable */
+const ETP_BIT:          u32 = common::BIT_15;           /* External Trigger Polarity, 0 = Rising Edge / Active High, 1 = Falling Edge / Active Low */
+const SMS3_BIT:         u32 = common::BIT_16;           /* Slave Mode Selection Bit 3 */
+
 /* SR */
 const UPDATE_BIT:       u32 = common::BIT_0;
+const CC1IF_BIT:        u32 = common::BIT_1;            /* Capture/Compare 1 Interrupt Flag, Shift By The Channel Index */
+const CC1OF_BIT:        u32 = common::BIT_9;            /* Capture/Compare 1 Overcapture Flag, Shift By The Channel Index */
+const BIF_BIT:          u32 = common::BIT_7;            /* Break Interrupt Flag */
+const B2IF_BIT:         u32 = common::BIT_8;            /* Break 2 Interrupt Flag */
+
+/* EGR */
+const UG_BIT:           u32 = common::BIT_0;            /* Update Generation, Reinitializes The Counter And Loads The Preloaded Registers */
+const CC1G_BIT:         u32 = common::BIT_1;            /* Capture/Compare 1 Generation, Shift By The Channel Index */
+
+/* DIER */
+const BIE_BIT:          u32 = common::BIT_7;            /* Break Interrupt Enable */
+const UDE_BIT:          u32 = common::BIT_8;            /* Update DMA Request Enable */
+
+/* CCMR */
+const OC1PE_BIT:        u32 = common::BIT_3;            /* Output Compare 1 Preload, 0 = CCR Written Immediately, 1 = CCR Loaded On Update Event */
+const OC2PE_BIT:        u32 = common::BIT_11;           /* Output Compare 2 Preload, 0 = CCR Written Immediately, 1 = CCR Loaded On Update Event */
+const OC1M3_BIT:        u32 = common::BIT_16;           /* Output Compare 1 Mode Bit 3, Shift By CCMR_SHIFT For Channel 2 */
 
 /* CCER */
 const CC1E_BIT:         u32 = common::BIT_0;            /* Capture/Compare Output Enable */
 const CC1P_BIT:         u32 = common::BIT_1;            /* Capture/Compare Output Polarity */
+const CC1NE_BIT:        u32 = common::BIT_2;            /* Capture/Compare Complementary Output Enable */
 const CC1NP_BIT:        u32 = common::BIT_3;            /* Capture/Compare Output Polarity */
 const CC2E_BIT:         u32 = common::BIT_4;            /* Capture/Compare Output Enable */
 const CC2P_BIT:         u32 = common::BIT_5;            /* Capture/Compare Output Polarity */
@@ -102,11 +363,35 @@ const CC4NP_BIT:        u32 = common::BIT_15;           /* Capture/Compare Outpu
 const CMS_OFFSET:       u32 = 5;                        /* 00 = Edge Aligned     01 = Center Aligned Down     10 = Center Aligned Up     11 - Center Aligned Up */
 const CKD_OFFSET:       u32 = 8;                        /* 00 = Tdts = Tclk_int  01 = Tdts = 2*Tclk_int       10 = Tdts = 4*Tclk_int     11 - Reserved */
 
+/* CR2 */
+const MMS_OFFSET:       u32 = 4;                        /* Master Mode Selection, Source Of TRGO */
+
+/* SMCR */
+const ETF_OFFSET:       u32 = 8;                        /* External Trigger Filter */
+const ETPS_OFFSET:      u32 = 12;                       /* External Trigger Prescaler */
+const SMS_OFFSET:       u32 = 0;                        /* Slave Mode 000: Disabled     100: Reset Mode     101: Gated Mode     110: Trigger Mode     111: External Clock Mode 1 */
+const TS_OFFSET:        u32 = 4;                        /* Trigger Selection 000: ITR0     001: ITR1     010: ITR2     011: ITR3     100: TI1F_ED     101: TI1FP1     110: TI2FP2     111: ETRF */
+
 /* CCMR */
 const CC1S_OFFSET:      u32 = 0;                        /* Capture Compare 1 Mode 00: CC2 channel is configured as output 01: CC1 channel is configured as input, IC1 is mapped on TI1 10: CC1 channel is configured as input, IC1 is mapped on TI1 11: CC1 channel is configured as input, IC1 is mapped on TRC. This mode is working only if an internal trigger input is selected through the TS bit (TIMx_SMCR register) */
 const OC1M_OFFSET:      u32 = 4;                        /* Output Compare 1 Mode 0110: PWM mode 1 - In upcounting, channel 1 is active as long as TIMx_CNT<TIMx_CCR1 0111: PWM mode 1 - In upcounting, channel 1 is inactive as long as TIMx_CNT<TIMx_CCR1 else active. In downcounting, channel 1 is active as long as TIMx_CNT>TIMx_CCR1 else inactive. */
 const CC2S_OFFSET:      u32 = 8;                        /* Capture Compare 2 Mode 00: CC2 channel is configured as output 01: CC2 channel is configured as input, IC2 is mapped on TI2 10: CC2 channel is configured as input, IC2 is mapped on TI1 11: CC2 channel is configured as input, IC2 is mapped on TRC. This mode is working only if an internal trigger input is selected through the TS bit (TIMx_SMCR register) */
-const OC2M_OFFSET:      u32 = 12;                       /* Output Compare 2 Mode 0110: PWM mode 1 - In upcounting, channel 2 is active as long as TIMx_CNT<TIMx_CCR2 0111: PWM mode 2 - In upcounting, channel 2 is inactive as long as TIMx_CNT<TIMx_CCR2 else active. In downcounting, channel 2 is active as long as TIMx_CNT>TIMx_CCR2 else inactive. */
+
+const IC1PSC_OFFSET:    u32 = 2;                        /* Input Capture 1 Prescaler */
+const IC1F_OFFSET:      u32 = 4;                        /* Input Capture 1 Filter 0000: No filter     0001 - 1111: N consecutive samples at fCK_INT or fDTS / x */
+const CCMR_SHIFT:       u32 = 8;                        /* Channel 2 And 4 Fields Are 8 Bits Above Channel 1 And 3 */
+
+/* BDTR */
+const DTG_OFFSET:       u32 = 0;                        /* Dead-Time Generator */
+const BKF_OFFSET:       u32 = 16;                       /* Break Filter */
+const BK2F_OFFSET:      u32 = 20;                       /* Break 2 Filter */
+
+/* DCR */
+const DBA_OFFSET:       u32 = 0;                        /* DMA Base Address, First Register Written By A Burst */
+const DBL_OFFSET:       u32 = 8;                        /* DMA Burst Length, 00000: 1 transfer ... 10001: 18 transfers */
+
+/* CCER */
+const CCER_SHIFT:       u32 = 4;                        /* Each Channel Has 4 Bits */
 
 /* CNT */
 const CLEAR_CNT:        u32 = 0;
@@ -116,6 +401,226 @@ const PWM_MODE1:        u32 = 6;
 const PWM_MODE2:        u32 = 7;
 const CCS_OUTPUT:       u32 = 0;
 
+/* DMA Burst */
+const BURST_MAX:        u32 = 18;                       /* CR1 To BDTR */
+
+/* Slave Mode */
+const SMS_DISABLED:     u32 = 0;
+const SMS_RESET:        u32 = 4;
+const SMS_GATED:        u32 = 5;
+const SMS_TRIGGER:      u32 = 6;
+const SMS_EXT_CLOCK1:   u32 = 7;
+
+// Lockstep start:
+// Each slave is put in trigger mode on the ITRx that carries the TRGO of the master, the master sends its enable on TRGO,
+// so setting CEN of the master starts every slave at a fixed offset. The trigger is resynchronized on the slave clock,
+// so the slaves start 1 - 2 timer clocks after the master. MSM does not remove this, it only delays the trigger input of
+// the master itself and a software CEN write does not pass through it, so MSM is left clear.
+/* Start The Master And Then All Slaves A Fixed 1 - 2 Timer Clocks Later, Each Slave Is Paired With The Trigger Input Connected To The Master */
+pub fn start_lockstep(master: &Timer, slaves: &[(&Timer, Trigger)]) {
+    master.stop();
+    master.clr_cnt();
+    master.set_master(MasterMode::Enable, false);
+
+    for (slave, trigger) in slaves.iter() {
+        slave.stop();
+        slave.clr_cnt();
+        slave.set_slave(SlaveMode::Trigger, *trigger);
+    }
+
+    master.start();
+}
+
+/* Capture/Compare Event Of The Channel */
+pub fn cc_event(ch: Channel) -> Event {
+    return match ch {
+        Channel::Ch1    =>  Event::Cc1,
+        Channel::Ch2    =>  Event::Cc2,
+        Channel::Ch3    =>  Event::Cc3,
+        Channel::Ch4    =>  Event::Cc4
+    };
+}
+
+/* DIER Enable Bit Of The Event */
+fn event_ie(event: Event) -> u32 {
+    return match event {
+        Event::Break2   =>  BIE_BIT,
+        Event::Cc1Over | Event::Cc2Over | Event::Cc3Over | Event::Cc4Over
+                        =>  CC1IF_BIT << (event as u32 - Event::Cc1Over as u32),
+        _               =>  1 << (event as u32)
+    };
+}
+
+/* Largest Auto Reload Value Of The Counter */
+pub fn max_arr(width: Width) -> u32 {
+    return match width {
+        Width::Bits16   =>  common::MASK_16_BIT,
+        Width::Bits32   =>  common::MASK_32_BIT
+    };
+}
+
+// Period solver:
+// The period in timer clocks T = (PSC + 1) x (ARR + 1), T is the requested period x timer clock and is not always a whole number.
+// T is kept in 1/256 timer clocks so the search stays in 64-bit math, finer than any count the timer can make.
+// Every prescaler from the smallest that lets ARR fit the counter width up to the largest that leaves ARR >= 1
+// is tried with its nearest ARR, at most 65536 steps. The pair with the least error wins,
+// ties keep the smaller prescaler for the finer resolution, an exact match stops the search.
+const PSC_RANGE:        u64 = (common::MASK_16_BIT as u64) + 1;
+const FRAC_BITS:        u32 = 8;
+const NS_PER_S_WIDE:    u128 = 1_000_000_000;
+
+/* Solve PSC And ARR For A Period Of whole + rem / den Timer Clocks, Max Is The Largest ARR + 1 */
+fn solve(whole: u64, rem: u64, den: u64, max: u64) -> Result<(u32, u32), ScaleError> {
+    let one = 1 << FRAC_BITS;
+
+    if den == 0 || (whole == 0 && rem == 0) {
+        return Err(ScaleError::Zero);
+    }
+    if whole > PSC_RANGE * max {
+        return Err(ScaleError::TooLong);
+    }
+
+    let target = (whole << FRAC_BITS) | ((rem << FRAC_BITS) / den);
+
+    if (2 * target) < (3 * one) {
+        return Err(ScaleError::TooShort);                           // Rounds to less than 2 clocks
+    }
+    if (2 * target) > ((2 * PSC_RANGE * max) + 1) * one {
+        return Err(ScaleError::TooLong);
+    }
+
+    let first = target.div_ceil(max * one).clamp(1, PSC_RANGE);     // Smallest PSC + 1 where ARR + 1 fits
+    let last = (target / (2 * one)).clamp(1, PSC_RANGE);            // Largest PSC + 1 where ARR + 1 can be 2
+    let mut best = (0, 0, u64::MAX);
+
+    for p in first..=last {
+        let a = (((2 * target) + (p * one)) / (2 * p * one)).min(max); // Nearest ARR + 1
+
+        if a < 2 {
+            break;
+        }
+
+        let err = (p * a * one).abs_diff(target);
+
+        if err < best.2 {
+            best = (p, a, err);
+        }
+        if err == 0 {
+            break;
+        }
+    }
+
+    if best.2 == u64::MAX {
+        return Err(ScaleError::TooShort);
+    }
+    return Ok(((best.0 - 1) as u32, (best.1 - 1) as u32));
+}
+
+/* Solve PSC And ARR For A Period In Nanoseconds, Timer Clock In Hz */
+pub fn solve_period(period_ns: u64, clk_hz: u32, width: Width) -> Result<Scale, ScaleError> {
+    if clk_hz == 0 {
+        return Err(ScaleError::Zero);
+    }
+
+    let sub = (period_ns % NS_PER_S) * clk_hz as u64;               // Under 2^62
+    let whole = (period_ns / NS_PER_S).saturating_mul(clk_hz as u64).saturating_add(sub / NS_PER_S);
+    let (psc, arr) = solve(whole, sub % NS_PER_S, NS_PER_S, max_arr(width) as u64 + 1)?;
+    return Ok(Scale::init(psc, arr, (psc as u64 + 1) * (arr as u64 + 1), clk_hz, period_ns as i64));
+}
+
+/* Solve PSC And ARR For An Update Frequency In Hz, Timer Clock In Hz */
+pub fn solve_frequency(freq_hz: u32, clk_hz: u32, width: Width) -> Result<Scale, ScaleError> {
+    if freq_hz == 0 {
+        return Err(ScaleError::Zero);
+    }
+
+    let (psc, arr) = solve((clk_hz / freq_hz) as u64, (clk_hz % freq_hz) as u64, freq_hz as u64, max_arr(width) as u64 + 1)?;
+    let requested = ((NS_PER_S + (freq_hz as u64 / 2)) / freq_hz as u64) as i64;
+    return Ok(Scale::init(psc, arr, (psc as u64 + 1) * (arr as u64 + 1), clk_hz, requested));
+}
+
+/* Solve PSC And ARR For A PWM Frequency In Hz, Timer Clock In Hz */
+/* Edge Aligned Periods Are ARR + 1 Counts, Center Aligned Count Up And Down So Periods Are 2 x ARR Counts */
+pub fn solve_pwm(freq_hz: u32, clk_hz: u32, width: Width, align: Align) -> Result<Scale, ScaleError> {
+    if let Align::Edge = align {
+        return solve_frequency(freq_hz, clk_hz, width);
+    }
+    if freq_hz == 0 {
+        return Err(ScaleError::Zero);
+    }
+
+    let half = 2 * freq_hz as u64;                                  // Solve the half period, ARR counts up then down
+    let (psc, counts) = solve(clk_hz as u64 / half, clk_hz as u64 % half, half, max_arr(width) as u64)?;
+    let arr = counts + 1;                                           // Solved as ARR + 1, the half period is ARR counts
+    let requested = ((NS_PER_S + (freq_hz as u64 / 2)) / freq_hz as u64) as i64;
+    return Ok(Scale::init(psc, arr, 2 * (psc as u64 + 1) * arr as u64, clk_hz, requested));
+}
+
+/* Dead-Time */
+const DTG_STEP1_MAX:    u64 = 127;                      /* DTG[7] = 0:      DT = DTG[6:0] x tDTS */
+const DTG_STEP2_MAX:    u64 = 254;                      /* DTG[7:6] = 10:   DT = (64 + DTG[5:0]) x 2 x tDTS */
+const DTG_STEP3_MAX:    u64 = 504;                      /* DTG[7:5] = 110:  DT = (32 + DTG[4:0]) x 8 x tDTS */
+const DTG_STEP4_MAX:    u64 = 1008;                     /* DTG[7:5] = 111:  DT = (32 + DTG[4:0]) x 16 x tDTS */
+const NS_PER_S:         u64 = 1_000_000_000;
+
+/* Dead-Time Generator Value For At Least The Requested Nanoseconds, Timer Clock In Hz With CKD = 00, None If Too Long */
+pub fn dead_time(ns: u32, clk_hz: u32) -> Option<u32> {
+    let ticks = (ns as u64 * clk_hz as u64).div_ceil(NS_PER_S);
+
+    if ticks <= DTG_STEP1_MAX {
+        return Some(ticks as u32);
+    } else if ticks <= DTG_STEP2_MAX {
+        return Some((0x80 | (ticks.div_ceil(2) - 64)) as u32);
+    } else if ticks <= DTG_STEP3_MAX {
+        return Some((0xC0 | (ticks.div_ceil(8) - 32)) as u32);
+    } else if ticks <= DTG_STEP4_MAX {
+        return Some((0xE0 | (ticks.div_ceil(16) - 32)) as u32);
+    }
+    return None;
+}
+
+/* Time Between Two Captures In Counts, Handles One Counter Wrap At ARR */
+pub fn capture_diff(first: u32, second: u32, arr: u32) -> u32 {
+    if second >= first {
+        return second - first;
+    }
+    return (arr - first) + second + 1;
+}
+
+/* Frequency In Hz Of A Period Of Counts, Timer Clock In Hz Before The Prescaler */
+pub fn counts_to_hz(clk_hz: u32, psc: u32, counts: u32) -> u32 {
+    if counts == 0 {
+        return 0;
+    }
+    return (clk_hz / (psc + 1)) / counts;
+}
+
+/* Duty Cycle In Tenths Of A Percent */
+pub fn duty_permille(high: u32, period: u32) -> u32 {
+    if period == 0 {
+        return 0;
+    }
+    return ((high as u64 * 1000) / period as u64) as u32;
+}
+
+/* Channel Index 0 - 3, Used To Shift The Per Channel Bits Of SR, DIER And CCER */
+fn index(ch: Channel) -> u32 {
+    return match ch {
+        Channel::Ch1    =>  0,
+        Channel::Ch2    =>  1,
+        Channel::Ch3    =>  2,
+        Channel::Ch4    =>  3
+    };
+}
+
+/* Shift Of The Channel Fields Within Its CCMR Register */
+fn ccmr_shift(ch: Channel) -> u32 {
+    return match ch {
+        Channel::Ch1 | Channel::Ch3     =>  0,
+        Channel::Ch2 | Channel::Ch4     =>  CCMR_SHIFT
+    };
+}
+
 impl Timer {
     /* Initialize The Structure */
     pub fn init(base: u32) -> Timer {
@@ -137,6 +642,7 @@ impl Timer {
             ccr2:   (base + CCR2)   as *mut u32,
             ccr3:   (base + CCR3)   as *mut u32,
             ccr4:   (base + CCR4)   as *mut u32,
+            bdtr:   (base + BDTR)   as *mut u32,
             dcr:    (base + DCR)    as *mut u32,
             dmar:   (base + DMAR)   as *mut u32,
             or:     (base + OR)     as *mut u32
@@ -171,7 +677,7 @@ impl Timer {
     
     /* Clear Interrupt Flag */
     pub fn clr_flag(&self) {
-        pointer::clr_ptr_vol_bit_u32(self.sr, UPDATE_BIT);
+        pointer::set_ptr_vol_raw_u32(self.sr, !UPDATE_BIT);
     }
 
     /* Start Timer */
@@ -200,20 +706,39 @@ impl Timer {
     }
     
     /* Set Time and Scaling Of The Timer */
-    pub fn set_scl(&self, time: u32, clk_speed: u32, prescl: u32) {  
-        let val;
-        let psc;
-        
-        if prescl == 0 {
-            val = (time * clk_speed) - 1;
-            psc = prescl;
-        } else {
-            val = ((time * clk_speed) / prescl) - 1;
-            psc = prescl - 1;
+    /* Prescaler Is A Divider, 0 And 1 Both Mean Undivided, Fails If The Period Is Zero Or Does Not Fit The Registers */
+    pub fn set_scl(&self, time: u32, clk_speed: u32, prescl: u32, width: Width) -> Result<(), ScaleError> {
+        let div = prescl.max(1) as u64;
+        let counts = (time as u64 * clk_speed as u64) / div;
+
+        if counts == 0 {
+            return Err(ScaleError::Zero);
         }
-    
-        pointer::set_ptr_vol_raw_u32(self.psc, psc);
-        pointer::set_ptr_vol_raw_u32(self.arr, val);
+        if counts > max_arr(width) as u64 + 1 || div > PSC_RANGE {
+            return Err(ScaleError::TooLong);
+        }
+
+        pointer::set_ptr_vol_raw_u32(self.psc, (div - 1) as u32);
+        pointer::set_ptr_vol_raw_u32(self.arr, (counts - 1) as u32);
+        return Ok(());
+    }
+
+    /* Set PSC And ARR To The Period In Nanoseconds With The Least Error, Timer Clock In Hz */
+    pub fn set_period(&self, period_ns: u64, clk_hz: u32, width: Width) -> Result<Scale, ScaleError> {
+        let scale = solve_period(period_ns, clk_hz, width)?;
+
+        self.set_psc(scale.psc);
+        self.set_arr(scale.arr);
+        return Ok(scale);
+    }
+
+    /* Set PSC And ARR To The Update Frequency In Hz With The Least Error, Timer Clock In Hz */
+    pub fn set_frequency(&self, freq_hz: u32, clk_hz: u32, width: Width) -> Result<Scale, ScaleError> {
+        let scale = solve_frequency(freq_hz, clk_hz, width)?;
+
+        self.set_psc(scale.psc);
+        self.set_arr(scale.arr);
+        return Ok(scale);
     }
 
     /* Simple Spin And Wait On A Timer */
@@ -247,7 +772,7 @@ impl Timer {
     /* Default Set Up For PWM */
     pub fn set_pwm_ch1(&self) {
         pointer::set_ptr_vol_u32(self.ccmr1, CC1S_OFFSET, CCS_MASK, CCS_OUTPUT);
-        pointer::set_ptr_vol_u32(self.ccmr1, OC1M_OFFSET, OCM_MASK, PWM_MODE1);
+        self.set_ocm(Channel::Ch1, PWM_MODE1);
         pointer::set_ptr_vol_bit_u32(self.ccer, CC1E_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC1P_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC1NP_BIT);
@@ -256,7 +781,7 @@ impl Timer {
     /* Default Set Up For PWM */
     pub fn set_pwm_ch2(&self) {
         pointer::set_ptr_vol_u32(self.ccmr1, CC2S_OFFSET, CCS_MASK, CCS_OUTPUT);
-        pointer::set_ptr_vol_u32(self.ccmr1, OC2M_OFFSET, OCM_MASK, PWM_MODE1);
+        self.set_ocm(Channel::Ch2, PWM_MODE1);
         pointer::set_ptr_vol_bit_u32(self.ccer, CC2E_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC2P_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC2NP_BIT);
@@ -265,7 +790,7 @@ impl Timer {
     /* Default Set Up For PWM */
     pub fn set_pwm_ch3(&self) {
         pointer::set_ptr_vol_u32(self.ccmr2, CC1S_OFFSET, CCS_MASK, CCS_OUTPUT);
-        pointer::set_ptr_vol_u32(self.ccmr2, OC1M_OFFSET, OCM_MASK, PWM_MODE1);
+        self.set_ocm(Channel::Ch3, PWM_MODE1);
         pointer::set_ptr_vol_bit_u32(self.ccer, CC3E_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC3P_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC3NP_BIT);
@@ -274,14 +799,699 @@ impl Timer {
     /* Default Set Up For PWM */
     pub fn set_pwm_ch4(&self) {
         pointer::set_ptr_vol_u32(self.ccmr2, CC2S_OFFSET, CCS_MASK, CCS_OUTPUT);
-        pointer::set_ptr_vol_u32(self.ccmr2, OC2M_OFFSET, OCM_MASK, PWM_MODE1);
+        self.set_ocm(Channel::Ch4, PWM_MODE1);
         pointer::set_ptr_vol_bit_u32(self.ccer, CC4E_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC4P_BIT);
         pointer::clr_ptr_vol_bit_u32(self.ccer, CC4NP_BIT);
     }
 
-    pub fn delay(&self, time: u32, clk_speed: u32, prescl: u32) {
-        self.set_scl(time, clk_speed, prescl);
+    /* Enable Capture/Compare Preload, CCR Is Only Loaded On The Update Event So A Period Is Never Cut Short */
+    pub fn set_preload(&self, ch: Channel) {
+        match ch {
+            Channel::Ch1    =>  pointer::set_ptr_vol_bit_u32(self.ccmr1, OC1PE_BIT),
+            Channel::Ch2    =>  pointer::set_ptr_vol_bit_u32(self.ccmr1, OC2PE_BIT),
+            Channel::Ch3    =>  pointer::set_ptr_vol_bit_u32(self.ccmr2, OC1PE_BIT),
+            Channel::Ch4    =>  pointer::set_ptr_vol_bit_u32(self.ccmr2, OC2PE_BIT)
+        }
+    }
+
+    /* Disable Capture/Compare Preload */
+    pub fn clr_preload(&self, ch: Channel) {
+        match ch {
+            Channel::Ch1    =>  pointer::clr_ptr_vol_bit_u32(self.ccmr1, OC1PE_BIT),
+            Channel::Ch2    =>  pointer::clr_ptr_vol_bit_u32(self.ccmr1, OC2PE_BIT),
+            Channel::Ch3    =>  pointer::clr_ptr_vol_bit_u32(self.ccmr2, OC1PE_BIT),
+            Channel::Ch4    =>  pointer::clr_ptr_vol_bit_u32(self.ccmr2, OC2PE_BIT)
+        }
+    }
+
+    /* Set The Capture/Compare Register Of The Channel */
+    pub fn set_ccr(&self, ch: Channel, cnt: u32) {
+        match ch {
+            Channel::Ch1    =>  self.set_pwm_ccr1(cnt),
+            Channel::Ch2    =>  self.set_pwm_ccr2(cnt),
+            Channel::Ch3    =>  self.set_pwm_ccr3(cnt),
+            Channel::Ch4    =>  self.set_pwm_ccr4(cnt)
+        }
+    }
+
+    /* Default Set Up For PWM On The Channel */
+    pub fn set_pwm(&self, ch: Channel) {
+        match ch {
+            Channel::Ch1    =>  self.set_pwm_ch1(),
+            Channel::Ch2    =>  self.set_pwm_ch2(),
+            Channel::Ch3    =>  self.set_pwm_ch3(),
+            Channel::Ch4    =>  self.set_pwm_ch4()
+        }
+    }
+
+    /* Get The Address Of The Capture/Compare Register, Used As The DMA Peripheral Address */
+    pub fn get_ccr_addr(&self, ch: Channel) -> u32 {
+        return match ch {
+            Channel::Ch1    =>  self.ccr1 as u32,
+            Channel::Ch2    =>  self.ccr2 as u32,
+            Channel::Ch3    =>  self.ccr3 as u32,
+            Channel::Ch4    =>  self.ccr4 as u32
+        };
+    }
+
+    /* Get The Auto Reload Value, The Number Of Counts In A Period Is ARR + 1 */
+    pub fn get_arr(&self) -> u32 {
+        return pointer::get_ptr_vol_raw_u32(self.arr);
+    }
+
+    /* Enable The DMA Request On The Update Event */
+    pub fn set_dma_update(&self) {
+        pointer::set_ptr_vol_bit_u32(self.dier, UDE_BIT);
+    }
+
+    /* Disable The DMA Request On The Update Event */
+    pub fn clr_dma_update(&self) {
+        pointer::clr_ptr_vol_bit_u32(self.dier, UDE_BIT);
+    }
+
+    // DMA burst:
+    // Every DMA request is turned into DBL + 1 accesses to DMAR, the timer redirects them to the consecutive registers from DBA,
+    // so one update event loads a whole set of registers (e.g. ARR, RCR and CCR1) from the buffer.
+    // The DMA channel moves 32-bit words from memory to the DMAR address, the transfer count is the total number of words.
+    /* Set The First Register And Number Of Registers Written Per Request, Returns False If The Burst Runs Past BDTR */
+    pub fn set_burst(&self, base: BurstReg, len: u32) -> bool {
+        if len == 0 || (base as u32 + len) > BURST_MAX {
+            return false;
+        }
+
+        pointer::set_ptr_vol_u32(self.dcr, DBA_OFFSET, DBA_MASK, base as u32);
+        pointer::set_ptr_vol_u32(self.dcr, DBL_OFFSET, DBL_MASK, len - 1);
+        return true;
+    }
+
+    /* Get The Number Of Registers Written Per Request */
+    pub fn get_burst_len(&self) -> u32 {
+        return ((pointer::get_ptr_vol_raw_u32(self.dcr) >> DBL_OFFSET) & DBL_MASK) + 1;
+    }
+
+    /* Get The Address Of DMAR, The Peripheral Address Of A Burst Transfer */
+    pub fn get_dmar_addr(&self) -> u32 {
+        return self.dmar as u32;
+    }
+
+    /* Set The PSC And ARR For A PWM Frequency From The Timer Clock In Hz With The Least Error */
+    /* The Alignment Is Set Here As It Changes The Counts Per Period */
+    pub fn set_pwm_freq(&self, clk_hz: u32, freq_hz: u32, width: Width, align: Align) -> Result<Scale, ScaleError> {
+        let scale = solve_pwm(freq_hz, clk_hz, width, align)?;
+
+        self.set_align(align);
+        self.set_psc(scale.psc);
+        self.set_arr(scale.arr);
+        return Ok(scale);
+    }
+
+    /* Set The Counter Alignment, Only Change While The Counter Is Stopped */
+    pub fn set_align(&self, align: Align) {
+        pointer::set_ptr_vol_u32(self.cr1, CMS_OFFSET, CMS_MASK, align as u32);
+    }
+
+    /* Auto Reload Preload, True: ARR Is Buffered And Only Applied On The Update Event */
+    pub fn set_arpe(&self, arpe: bool) {
+        match arpe {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.cr1, ARPE_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.cr1, ARPE_BIT)
+        }
+    }
+
+    /* Set Up The Channel As A PWM Output With The Mode, Polarity And CCR Preload */
+    pub fn set_pwm_config(&self, ch: Channel, mode: PwmMode, pol: Polarity, preload: bool) {
+        let ccmr = self.get_ccmr(ch);
+        let shift = ccmr_shift(ch);
+        let ccer = index(ch) * CCER_SHIFT;
+
+        pointer::clr_ptr_vol_bit_u32(self.ccer, CC1E_BIT << ccer);
+        pointer::set_ptr_vol_u32(ccmr, CC1S_OFFSET + shift, CCS_MASK, CCS_OUTPUT);
+        self.set_ocm(ch, mode as u32);
+
+        match preload {
+            true    =>  self.set_preload(ch),
+            false   =>  self.clr_preload(ch)
+        }
+
+        match pol {
+            Polarity::ActiveLow     =>  pointer::set_ptr_vol_bit_u32(self.ccer, CC1P_BIT << ccer),
+            Polarity::ActiveHigh    =>  pointer::clr_ptr_vol_bit_u32(self.ccer, CC1P_BIT << ccer)
+        }
+
+        pointer::set_ptr_vol_bit_u32(self.ccer, CC1E_BIT << ccer);
+    }
+
+    /* Set The Duty As A Fraction num / den Of The Period, Clamped To 100% */
+    pub fn set_duty(&self, ch: Channel, num: u32, den: u32) {
+        if den == 0 {
+            return;
+        }
+
+        let arr = self.get_arr() as u64;
+        let period = if pointer::get_ptr_vol_u32(self.cr1, CMS_OFFSET, CMS_MASK) == 0 { arr + 1 } else { arr };
+        let ccr = (period * num.min(den) as u64) / den as u64;
+
+        self.set_ccr(ch, ccr as u32);
+    }
+
+    /* Set The Duty In Percent, 0 - 100 */
+    pub fn set_duty_percent(&self, ch: Channel, pct: u32) {
+        self.set_duty(ch, pct, 100);
+    }
+
+    /* Set The Prescaler, The Counter Clock Is The Timer Clock / (PSC + 1) */
+    pub fn set_psc(&self, psc: u32) {
+        pointer::set_ptr_vol_raw_u32(self.psc, psc);
+    }
+
+    pub fn get_psc(&self) -> u32 {
+        return pointer::get_ptr_vol_raw_u32(self.psc);
+    }
+
+    /* Set The Auto Reload Value */
+    pub fn set_arr(&self, arr: u32) {
+        pointer::set_ptr_vol_raw_u32(self.arr, arr);
+    }
+
+    /* Get The Capture/Compare Register Of The Channel, Reading A Capture Clears The CCxIF Flag */
+    pub fn get_ccr(&self, ch: Channel) -> u32 {
+        return match ch {
+            Channel::Ch1    =>  pointer::get_ptr_vol_raw_u32(self.ccr1),
+            Channel::Ch2    =>  pointer::get_ptr_vol_raw_u32(self.ccr2),
+            Channel::Ch3    =>  pointer::get_ptr_vol_raw_u32(self.ccr3),
+            Channel::Ch4    =>  pointer::get_ptr_vol_raw_u32(self.ccr4)
+        };
+    }
+
+    // Input capture procedure:
+    // 1. Select the active input, CCxS != 00 (the channel becomes an input and CCRx becomes read only).
+    // 2. Program the input filter ICxF to the sampling needed to reject noise on the input.
+    // 3. Select the active edge with CCxP and CCxNP.
+    // 4. Program the input prescaler ICxPSC.
+    // 5. Enable the capture with CCxE.
+    // When a capture occurs the counter is latched in CCRx and CCxIF is set, if CCxIF was already set CCxOF is also set.
+    // CCxIF is cleared by reading CCRx, CCxOF is cleared by writing it to 0.
+    /* Set Up The Channel As An Input Capture, Filter Is The ICxF Value 0 - 15 */
+    pub fn set_capture(&self, ch: Channel, input: CaptureInput, edge: Edge, psc: CapturePsc, filter: u32) {
+        let ccmr = self.get_ccmr(ch);
+        let shift = ccmr_shift(ch);
+        let ccer = index(ch) * CCER_SHIFT;
+
+        pointer::clr_ptr_vol_bit_u32(self.ccer, CC1E_BIT << ccer);
+        pointer::set_ptr_vol_u32(ccmr, CC1S_OFFSET + shift, CCS_MASK, input as u32);
+        pointer::set_ptr_vol_u32(ccmr, IC1PSC_OFFSET + shift, ICPSC_MASK, psc as u32);
+        pointer::set_ptr_vol_u32(ccmr, IC1F_OFFSET + shift, ICF_MASK, filter);
+
+        let pol = match edge {
+            Edge::Rising    =>  0,
+            Edge::Falling   =>  CC1P_BIT,
+            Edge::Both      =>  CC1P_BIT | CC1NP_BIT
+        };
+
+        pointer::clr_ptr_vol_bit_u32(self.ccer, CCP_MASK << ccer);
+        pointer::set_ptr_vol_bit_u32(self.ccer, pol << ccer);
+        pointer::set_ptr_vol_bit_u32(self.ccer, CC1E_BIT << ccer);
+    }
+
+    // Status flags:
+    // SR flags are set by hardware and cleared by writing 0, writing 1 has no effect.
+    // A read-modify-write of SR writes back 0 for any flag set after the read and loses it,
+    // so flags are cleared by writing the inverted flag mask.
+    /* Enable The Interrupt Of The Event */
+    pub fn set_event_interrupt(&self, event: Event) {
+        pointer::set_ptr_vol_bit_u32(self.dier, event_ie(event));
+    }
+
+    /* Disable The Interrupt Of The Event */
+    pub fn clr_event_interrupt(&self, event: Event) {
+        pointer::clr_ptr_vol_bit_u32(self.dier, event_ie(event));
+    }
+
+    /* Get If The Event Flag Is Set */
+    pub fn get_event(&self, event: Event) -> bool {
+        return pointer::get_ptr_vol_bit_u32(self.sr, 1 << (event as u32));
+    }
+
+    /* Clear The Event Flag Without Touching The Others */
+    pub fn clr_event(&self, event: Event) {
+        pointer::set_ptr_vol_raw_u32(self.sr, !(1 << (event as u32)));
+    }
+
+    /* Get The Raw Status Flags, Bit n Is Set For Event n */
+    pub fn get_events(&self) -> u32 {
+        return pointer::get_ptr_vol_raw_u32(self.sr);
+    }
+
+    /* Clear The Flags In The Mask, Usually The Flags Just Read With get_events */
+    pub fn clr_events(&self, mask: u32) {
+        pointer::set_ptr_vol_raw_u32(self.sr, !mask);
+    }
+
+    /* Get The Capture If One Occurred And If Captures Were Lost Since The Last Read (Overcapture) */
+    pub fn get_capture(&self, ch: Channel) -> Option<(u32, bool)> {
+        let i = index(ch);
+
+        if !pointer::get_ptr_vol_bit_u32(self.sr, CC1IF_BIT << i) {
+            return None;
+        }
+
+        let val = self.get_ccr(ch);
+        let over = pointer::get_ptr_vol_bit_u32(self.sr, CC1OF_BIT << i);
+
+        if over {
+            pointer::set_ptr_vol_raw_u32(self.sr, !(CC1OF_BIT << i));
+        }
+        return Some((val, over));
+    }
+
+    // PWM input mode, two captures on one input:
+    // • The channel of the pin captures the period on the rising edge (direct mapping).
+    // • The other channel of the pair captures the high time on the falling edge (indirect mapping).
+    // • Slave reset mode on the filtered input resets the counter on every rising edge.
+    // Only channels 1 and 2 can be used, TI1FP1 and TI2FP2 are the only inputs that reach the slave mode controller.
+    /* Set Up PWM Input Mode On The Pin Of Channel 1 Or 2, Returns False For Channel 3 Or 4 */
+    pub fn set_pwm_input(&self, ch: Channel, filter: u32) -> bool {
+        let (period, high, ts) = match ch {
+            Channel::Ch1    =>  (Channel::Ch1, Channel::Ch2, Trigger::Ti1fp1),
+            Channel::Ch2    =>  (Channel::Ch2, Channel::Ch1, Trigger::Ti2fp2),
+            _               =>  return false
+        };
+
+        self.set_capture(period, CaptureInput::Direct, Edge::Rising, CapturePsc::Div1, filter);
+        self.set_capture(high, CaptureInput::Indirect, Edge::Falling, CapturePsc::Div1, filter);
+
+        self.set_slave(SlaveMode::Reset, ts);
+        return true;
+    }
+
+    /* Get The Period And High Time In Counts Of The PWM Input On Channel 1 Or 2, None Until A Period Has Been Captured */
+    pub fn get_pwm_input(&self, ch: Channel) -> Option<(u32, u32)> {
+        let high = match ch {
+            Channel::Ch1    =>  Channel::Ch2,
+            Channel::Ch2    =>  Channel::Ch1,
+            _               =>  return None
+        };
+
+        return self.get_capture(ch).map(|(period, _)| (period, self.get_ccr(high)));
+    }
+
+    // Encoder interface mode:
+    // TI1 and TI2 are the two quadrature inputs, the counter counts up or down on their edges depending on the level of the other input.
+    // • CC1S = 01 and CC2S = 01, IC1 on TI1 and IC2 on TI2, the input filters reject glitches.
+    // • CC1P / CC2P invert the sense of the input, which reverses the counting direction.
+    // • SMS selects the edges counted, mode 3 counts every edge of both inputs (4 counts per encoder line).
+    // The counter wraps at ARR, so ARR is set to its maximum, DIR in CR1 follows the direction of rotation.
+    /* Set Up The Timer As A Quadrature Encoder Interface On Channels 1 And 2, Filter Is The ICxF Value 0 - 15 */
+    pub fn set_encoder(&self, mode: EncoderMode, filter: u32, invert1: bool, invert2: bool) {
+        self.stop();
+
+        let edge1 = if invert1 { Edge::Falling } else { Edge::Rising };
+        let edge2 = if invert2 { Edge::Falling } else { Edge::Rising };
+
+        self.set_capture(Channel::Ch1, CaptureInput::Direct, edge1, CapturePsc::Div1, filter);
+        self.set_capture(Channel::Ch2, CaptureInput::Direct, edge2, CapturePsc::Div1, filter);
+
+        pointer::clr_ptr_vol_bit_u32(self.smcr, SMS3_BIT);
+        pointer::set_ptr_vol_u32(self.smcr, SMS_OFFSET, SMS_MASK, mode as u32);
+
+        self.set_arr(common::MASK_32_BIT);                  // Reads back as 0xFFFF on 16-bit timers
+        self.clr_cnt();
+    }
+
+    /* Get The Counting Direction, In Encoder Mode This Is The Direction Of Rotation */
+    pub fn get_direction(&self) -> Direction {
+        if pointer::get_ptr_vol_bit_u32(self.cr1, DIR_BIT) {
+            return Direction::Downcount;
+        }
+        return Direction::Upcount;
+    }
+
+    // Complementary outputs (advanced timers, channels 1 - 3):
+    // OCx and OCxN are generated from the same reference with a dead-time inserted on each rising edge,
+    // CCxNE enables OCxN and CCxNP sets its polarity. The outputs are only driven while MOE is set,
+    // a break clears MOE and puts the outputs in their idle state, AOE sets MOE again on the next update event.
+    /* Enable The Complementary Output Of The Channel, Returns False For Channel 4 */
+    pub fn set_complementary(&self, ch: Channel, active_low: bool) -> bool {
+        if ch == Channel::Ch4 {
+            return false;
+        }
+
+        let ccer = index(ch) * CCER_SHIFT;
+
+        match active_low {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.ccer, CC1NP_BIT << ccer),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.ccer, CC1NP_BIT << ccer)
+        }
+        pointer::set_ptr_vol_bit_u32(self.ccer, CC1NE_BIT << ccer);
+        return true;
+    }
+
+    /* Disable The Complementary Output Of The Channel */
+    pub fn clr_complementary(&self, ch: Channel) {
+        pointer::clr_ptr_vol_bit_u32(self.ccer, CC1NE_BIT << (index(ch) * CCER_SHIFT));
+    }
+
+    /* Set The Dead-Time In Nanoseconds, Timer Clock In Hz, Returns False If The Dead-Time Is Too Long For The Clock */
+    pub fn set_dead_time(&self, ns: u32, clk_hz: u32) -> bool {
+        return match dead_time(ns, clk_hz) {
+            Some(dtg) => {
+                pointer::set_ptr_vol_u32(self.bdtr, DTG_OFFSET, DTG_MASK, dtg);
+                true
+            },
+            None => false
+        };
+    }
+
+    /* Set The Off State Of The Outputs, Run: Outputs Driven Inactive While Disabled, Idle: Outputs Driven Idle After A Break */
+    pub fn set_off_state(&self, run: bool, idle: bool) {
+        match run {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.bdtr, OSSR_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.bdtr, OSSR_BIT)
+        }
+
+        match idle {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.bdtr, OSSI_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.bdtr, OSSI_BIT)
+        }
+    }
+
+    /* Enable The Break Input, Filter Is The BKF Value 0 - 15 */
+    pub fn set_break(&self, pol: BreakPolarity, filter: u32) {
+        match pol {
+            BreakPolarity::ActiveHigh   =>  pointer::set_ptr_vol_bit_u32(self.bdtr, BKP_BIT),
+            BreakPolarity::ActiveLow    =>  pointer::clr_ptr_vol_bit_u32(self.bdtr, BKP_BIT)
+        }
+        pointer::set_ptr_vol_u32(self.bdtr, BKF_OFFSET, BKF_MASK, filter);
+        pointer::set_ptr_vol_bit_u32(self.bdtr, BKE_BIT);
+    }
+
+    /* Disable The Break Input */
+    pub fn clr_break(&self) {
+        pointer::clr_ptr_vol_bit_u32(self.bdtr, BKE_BIT);
+    }
+
+    /* Enable The Break 2 Input, Filter Is The BK2F Value 0 - 15 */
+    pub fn set_break2(&self, pol: BreakPolarity, filter: u32) {
+        match pol {
+            BreakPolarity::ActiveHigh   =>  pointer::set_ptr_vol_bit_u32(self.bdtr, BK2P_BIT),
+            BreakPolarity::ActiveLow    =>  pointer::clr_ptr_vol_bit_u32(self.bdtr, BK2P_BIT)
+        }
+        pointer::set_ptr_vol_u32(self.bdtr, BK2F_OFFSET, BKF_MASK, filter);
+        pointer::set_ptr_vol_bit_u32(self.bdtr, BK2E_BIT);
+    }
+
+    /* Disable The Break 2 Input */
+    pub fn clr_break2(&self) {
+        pointer::clr_ptr_vol_bit_u32(self.bdtr, BK2E_BIT);
+    }
+
+    /* Enable The Main Output, Must Be Set For Any Output Of An Advanced Timer To Be Driven */
+    pub fn set_moe(&self) {
+        pointer::set_ptr_vol_bit_u32(self.bdtr, MOE_BIT);
+    }
+
+    /* Disable The Main Output */
+    pub fn clr_moe(&self) {
+        pointer::clr_ptr_vol_bit_u32(self.bdtr, MOE_BIT);
+    }
+
+    /* Get The Main Output Enable, False After A Break Until Set Again */
+    pub fn get_moe(&self) -> bool {
+        return pointer::get_ptr_vol_bit_u32(self.bdtr, MOE_BIT);
+    }
+
+    /* Automatic Output Enable, True: MOE Is Set Again On The Next Update Event After The Break Clears */
+    pub fn set_aoe(&self, aoe: bool) {
+        match aoe {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.bdtr, AOE_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.bdtr, AOE_BIT)
+        }
+    }
+
+    /* Enable The Break Interrupt */
+    pub fn set_break_interrupt(&self) {
+        pointer::set_ptr_vol_bit_u32(self.dier, BIE_BIT);
+    }
+
+    /* Handle A Break Event From The Break Interrupt, The Callback Gets True If Break 2 Caused It, Returns False If No Break Was Pending */
+    pub fn on_break(&self, callback: fn(&Timer, bool)) -> bool {
+        let brk = pointer::get_ptr_vol_bit_u32(self.sr, BIF_BIT);
+        let brk2 = pointer::get_ptr_vol_bit_u32(self.sr, B2IF_BIT);
+
+        if !brk && !brk2 {
+            return false;
+        }
+
+        let seen = match brk { true => BIF_BIT, false => 0 } | match brk2 { true => B2IF_BIT, false => 0 };
+
+        pointer::set_ptr_vol_raw_u32(self.sr, !seen);                 // rc_w0, clear only what was seen, a later break stays pending
+        callback(self, brk2);
+        return true;
+    }
+
+    // Master/slave:
+    // TRGO is the trigger output of the timer, routed to the ITRx inputs of other timers and to the ADC / DAC trigger selection.
+    // The slave mode controller acts on the selected trigger input, TS must only be changed while the slave mode is disabled.
+    // • Reset: a rising trigger edge reinitializes the counter.
+    // • Gated: the counter runs while the trigger is high.
+    // • Trigger: a rising trigger edge sets CEN, the counter is not stopped again.
+    // • External clock 1: rising edges of the trigger clock the counter.
+    // • External clock 2: edges of ETR clock the counter, set up the input with set_etr.
+    // • Reset + trigger: a rising trigger edge reinitializes the counter and sets CEN, used with the retriggerable one-pulse modes.
+    /* Select The TRGO Source, MSM Delays The Trigger Input Of This Timer To Keep It In Step With Its Slaves */
+    pub fn set_master(&self, mode: MasterMode, msm: bool) {
+        pointer::set_ptr_vol_u32(self.cr2, MMS_OFFSET, MMS_MASK, mode as u32);
+
+        match msm {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.smcr, MSM_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.smcr, MSM_BIT)
+        }
+    }
+
+    /* Set The Slave Mode And Its Trigger Input, The Trigger Is Ignored In External Clock Mode 2 */
+    pub fn set_slave(&self, mode: SlaveMode, trigger: Trigger) {
+        let sms = match mode {
+            SlaveMode::Disabled     =>  SMS_DISABLED,
+            SlaveMode::Reset        =>  SMS_RESET,
+            SlaveMode::Gated        =>  SMS_GATED,
+            SlaveMode::Trigger      =>  SMS_TRIGGER,
+            SlaveMode::ExtClock1    =>  SMS_EXT_CLOCK1,
+            SlaveMode::ExtClock2    =>  SMS_DISABLED,
+            SlaveMode::ResetTrigger =>  SMS_DISABLED                // SMS[2:0] = 000 with SMS3 set
+        };
+
+        self.clr_slave();
+        pointer::set_ptr_vol_u32(self.smcr, TS_OFFSET, TS_MASK, trigger as u32);
+
+        match mode {
+            SlaveMode::ExtClock2    =>  pointer::set_ptr_vol_bit_u32(self.smcr, ECE_BIT),
+            SlaveMode::ResetTrigger =>  pointer::set_ptr_vol_bit_u32(self.smcr, SMS3_BIT),
+            _                       =>  {}
+        }
+        pointer::set_ptr_vol_u32(self.smcr, SMS_OFFSET, SMS_MASK, sms);
+    }
+
+    /* Disable The Slave Mode And External Clock Mode 2, The Counter Runs On The Internal Clock */
+    pub fn clr_slave(&self) {
+        pointer::set_ptr_vol_u32(self.smcr, SMS_OFFSET, SMS_MASK, SMS_DISABLED);
+        pointer::clr_ptr_vol_bit_u32(self.smcr, SMS3_BIT);
+        pointer::clr_ptr_vol_bit_u32(self.smcr, ECE_BIT);
+    }
+
+    /* Set Up The External Trigger Input, Filter Is The ETF Value 0 - 15, Invert Counts Falling Edges */
+    pub fn set_etr(&self, psc: EtrPsc, filter: u32, invert: bool) {
+        match invert {
+            true    =>  pointer::set_ptr_vol_bit_u32(self.smcr, ETP_BIT),
+            false   =>  pointer::clr_ptr_vol_bit_u32(self.smcr, ETP_BIT)
+        }
+        pointer::set_ptr_vol_u32(self.smcr, ETPS_OFFSET, ETPS_MASK, psc as u32);
+        pointer::set_ptr_vol_u32(self.smcr, ETF_OFFSET, ETF_MASK, filter);
+    }
+
+    // Output compare modes:
+    // • Active / inactive / toggle on match change OCxREF when CNT = CCR, toggle gives a square wave of half the update frequency.
+    // • Forced active / inactive drive OCxREF directly, independent of the counter.
+    // • Retriggerable OPM starts a pulse of CCR counts on every trigger in slave mode ResetTrigger (combined reset + trigger).
+    // • Combined PWM ANDs (mode 1) or ORs (mode 2) OCxREF with the reference of the other channel of the pair.
+    // • Asymmetric PWM in center aligned mode uses CCR of the odd channel counting up and CCR of the even channel counting down.
+    // Combined and asymmetric modes and the 4th mode bit only exist on the advanced and general purpose timers with 4 channels.
+    /* Set Up The Channel As An Output Compare Output, Preload Delays CCR Changes To The Next Update Event */
+    pub fn set_output_compare(&self, ch: Channel, mode: OutputMode, pol: Polarity, preload: bool) {
+        let ccmr = self.get_ccmr(ch);
+        let ccer = index(ch) * CCER_SHIFT;
+
+        pointer::clr_ptr_vol_bit_u32(self.ccer, CC1E_BIT << ccer);
+        pointer::set_ptr_vol_u32(ccmr, CC1S_OFFSET + ccmr_shift(ch), CCS_MASK, CCS_OUTPUT);
+        self.set_ocm(ch, mode as u32);
+
+        match preload {
+            true    =>  self.set_preload(ch),
+            false   =>  self.clr_preload(ch)
+        }
+
+        match pol {
+            Polarity::ActiveLow     =>  pointer::set_ptr_vol_bit_u32(self.ccer, CC1P_BIT << ccer),
+            Polarity::ActiveHigh    =>  pointer::clr_ptr_vol_bit_u32(self.ccer, CC1P_BIT << ccer)
+        }
+
+        pointer::set_ptr_vol_bit_u32(self.ccer, CC1E_BIT << ccer);
+    }
+
+    /* Change The Output Compare Mode Of A Running Channel, Used To Force The Output Active Or Inactive */
+    pub fn set_output_mode(&self, ch: Channel, mode: OutputMode) {
+        self.set_ocm(ch, mode as u32);
+    }
+
+    /* Square Wave On The Channel In Toggle Mode, The Update Frequency Is Twice The Output Frequency, Timer Clock In Hz */
+    pub fn set_square_wave(&self, ch: Channel, freq_hz: u32, clk_hz: u32, width: Width) -> Result<Scale, ScaleError> {
+        let freq = match freq_hz.checked_mul(2) {
+            Some(freq)  =>  freq,
+            None        =>  return Err(ScaleError::TooShort)
+        };
+        let scale = self.set_frequency(freq, clk_hz, width)?;
+
+        self.set_ccr(ch, 0);
+        self.set_output_compare(ch, OutputMode::Toggle, Polarity::ActiveHigh, false);
+        return Ok(scale);
+    }
+
+    /* Generate An Update Event, PSC, ARR And The Preloaded CCR Are Loaded And The Counter Restarts, The Update Flag Is Cleared */
+    pub fn set_update_event(&self) {
+        pointer::set_ptr_vol_raw_u32(self.egr, UG_BIT);
+        self.clr_flag();
+    }
+
+    /* Generate A Capture/Compare Event On The Channel, Sets CCxIF And Raises Its Interrupt If Enabled, An Output Is Not Changed */
+    pub fn set_cc_event(&self, ch: Channel) {
+        pointer::set_ptr_vol_raw_u32(self.egr, CC1G_BIT << index(ch));
+    }
+
+    // One-pulse mode:
+    // The counter sits at 0 until the trigger input sets CEN (slave trigger mode), counts to ARR and stops on the update event (OPM).
+    // PWM mode 2 keeps the output inactive while CNT < CCR and active from CCR to ARR, so:
+    // • Delay = CCR counts from the trigger edge to the start of the pulse, at least 1 so the output has a rising edge.
+    // • Pulse = ARR + 1 - CCR counts.
+    // The timer is armed again as soon as the pulse ends, the next trigger edge starts a new pulse.
+    // TI1FP1 / TI2FP2 use channel 1 / 2 as the trigger input on its rising edge, set_one_pulse_edge selects the falling edge.
+    // The output must be another channel. Advanced timers also need MOE.
+    /* Set Up A Pulse On The Output Channel After Each Trigger Edge, Delay And Pulse In Nanoseconds, Timer Clock In Hz */
+    /* Returns The Timer Scale, The Delay Is Rounded To Whole Counts Of The Chosen Prescaler With A Minimum Of 1 Count */
+    pub fn set_one_pulse(&self, out: Channel, trigger: Trigger, delay_ns: u64, pulse_ns: u64, clk_hz: u32, width: Width) -> Result<Scale, ScaleError> {
+        if pulse_ns == 0 {
+            return Err(ScaleError::Zero);
+        }
+
+        let total = match delay_ns.checked_add(pulse_ns) {
+            Some(total) =>  total,
+            None        =>  return Err(ScaleError::TooLong)
+        };
+        let scale = solve_period(total, clk_hz, width)?;
+        let step = (scale.get_psc() as u128 + 1) * NS_PER_S_WIDE;
+        let ccr = ((((delay_ns as u128 * clk_hz as u128) + (step / 2)) / step) as u32).max(1);     // CCR = 0 would hold the output active
+
+        if ccr > scale.get_arr() {
+            return Err(ScaleError::TooShort);                   // Pulse rounds to no counts
+        }
+
+        self.stop();
+        self.clr_slave();
+
+        match trigger {
+            Trigger::Ti1fp1 =>  self.set_capture(Channel::Ch1, CaptureInput::Direct, Edge::Rising, CapturePsc::Div1, 0),
+            Trigger::Ti2fp2 =>  self.set_capture(Channel::Ch2, CaptureInput::Direct, Edge::Rising, CapturePsc::Div1, 0),
+            _               =>  {}
+        }
+
+        self.set_psc(scale.get_psc());
+        self.set_arr(scale.get_arr());
+        self.set_ccr(out, ccr);
+        self.set_output_compare(out, OutputMode::Pwm2, Polarity::ActiveHigh, true);
+        self.set_update_event();                                // Load PSC before the first trigger
+
+        pointer::set_ptr_vol_bit_u32(self.cr1, OPM_BIT);
+        self.set_slave(SlaveMode::Trigger, trigger);
+        return Ok(scale);
+    }
+
+    /* Set The Edge Of The TI1FP1 / TI2FP2 Trigger, Returns False For Both Edges Or Another Trigger */
+    pub fn set_one_pulse_edge(&self, trigger: Trigger, edge: Edge) -> bool {
+        let ch = match trigger {
+            Trigger::Ti1fp1 =>  Channel::Ch1,
+            Trigger::Ti2fp2 =>  Channel::Ch2,
+            _               =>  return false
+        };
+
+        if let Edge::Both = edge {
+            return false;                                       // Both edges is TI1F_ED, not a polarity of TI1FP1 / TI2FP2
+        }
+
+        self.set_capture(ch, CaptureInput::Direct, edge, CapturePsc::Div1, 0);
+        return true;
+    }
+
+    /* Re-Arm After A Pulse Was Cut Short By stop, The Counter Returns To 0 And Waits For The Next Trigger Edge */
+    pub fn arm_one_pulse(&self) {
+        self.stop();
+        self.clr_cnt();
+        self.clr_flag();
+    }
+
+    /* Get If A Pulse Is In Progress */
+    pub fn get_one_pulse_busy(&self) -> bool {
+        return pointer::get_ptr_vol_bit_u32(self.cr1, EN_BIT);
+    }
+
+    // Repetition counter (TIM1, TIM8, TIM15, TIM16):
+    // The update event only fires when the repetition counter reaches 0, so update interrupts, DMA requests and
+    // the loading of preloaded registers happen once every REP + 1 counter periods.
+    // In center aligned mode the repetition counter counts at both overflow and underflow, so an even number of
+    // half periods (REP odd) keeps the update event at the same end of the period: REP = 1 after an update at the valley
+    // updates only at the valley. RCR is preloaded and is only used after the next update event.
+    /* Set The Number Of Counter Periods (Half Periods In Center Aligned Mode) Per Update Event, 1 - 65536 */
+    /* Returns False If The Timer Has No Repetition Counter Or The Count Does Not Fit It */
+    pub fn set_repetition(&self, periods: u32) -> bool {
+        if periods == 0 || (periods - 1) > REP_MASK {
+            return false;
+        }
+
+        pointer::set_ptr_vol_raw_u32(self.rcr, periods - 1);
+
+        if pointer::get_ptr_vol_raw_u32(self.rcr) != (periods - 1) {
+            pointer::set_ptr_vol_raw_u32(self.rcr, 0);
+            return false;                                       // Reserved on timers without a repetition counter, reads back 0
+        }
+        return true;
+    }
+
+    /* Get The Number Of Counter Periods Per Update Event */
+    pub fn get_repetition(&self) -> u32 {
+        return (pointer::get_ptr_vol_raw_u32(self.rcr) & REP_MASK) + 1;
+    }
+
+    /* Write The 4-Bit OCxM Field, Bit 3 Is Separate From Bits 2:0 */
+    fn set_ocm(&self, ch: Channel, mode: u32) {
+        let ccmr = self.get_ccmr(ch);
+        let shift = ccmr_shift(ch);
+
+        pointer::set_ptr_vol_u32(ccmr, OC1M_OFFSET + shift, OCM_MASK, mode & OCM_MASK);
+
+        match (mode & common::BIT_3) != 0 {
+            true    =>  pointer::set_ptr_vol_bit_u32(ccmr, OC1M3_BIT << shift),
+            false   =>  pointer::clr_ptr_vol_bit_u32(ccmr, OC1M3_BIT << shift)
+        }
+    }
+
+    fn get_ccmr(&self, ch: Channel) -> *mut u32 {
+        return match ch {
+            Channel::Ch1 | Channel::Ch2     =>  self.ccmr1,
+            Channel::Ch3 | Channel::Ch4     =>  self.ccmr2
+        };
+    }
+
+    /* Spin For The Time, Scaled As In set_scl, Fails Without Waiting If The Time Does Not Fit The Registers */
+    pub fn delay(&self, time: u32, clk_speed: u32, prescl: u32, width: Width) -> Result<(), ScaleError> {
+        self.set_scl(time, clk_speed, prescl, width)?;
         self.start();
         self.clr_flag();
 
@@ -292,5 +1502,147 @@ impl Timer {
         self.stop();
         self.clr_cnt();
         self.clr_flag();
+        return Ok(());
+    }
+}
+
+impl Scale {
+    fn init(psc: u32, arr: u32, counts: u64, clk_hz: u32, requested_ns: i64) -> Scale {
+        let clk = clk_hz as u64;
+        let period_ns = ((counts / clk) * NS_PER_S) + ((((counts % clk) * NS_PER_S) + (clk / 2)) / clk);
+
+        return Scale {
+            psc,
+            arr,
+            period_ns,
+            error_ns:   period_ns as i64 - requested_ns
+        };
+    }
+
+    pub fn get_psc(&self) -> u32 {
+        return self.psc;
+    }
+
+    pub fn get_arr(&self) -> u32 {
+        return self.arr;
+    }
+
+    /* Achieved Period In Nanoseconds */
+    pub fn get_period_ns(&self) -> u64 {
+        return self.period_ns;
+    }
+
+    /* Achieved Period - Requested Period In Nanoseconds */
+    pub fn get_error_ns(&self) -> i64 {
+        return self.error_ns;
+    }
+}
+
+/* Longest Single Wait On The 16 Bit Counter, Half The Range So A Late Read Can Not Miss The End And Wait A Full Wrap */
+const DELAY_CHUNK_US: u32 = common::BIT_15;
+
+/* Spin Until The Counter Has Advanced By us Ticks, Long Waits Are Split Into Chunks That Fit The 16 Bit Wrap */
+fn spin_us<F: Fn() -> u32>(us: u32, cnt: F) {
+    let mut left = us;
+
+    while left > 0 {
+        let chunk = left.min(DELAY_CHUNK_US);
+        let start = cnt();
+
+        while (cnt().wrapping_sub(start) & common::MASK_16_BIT) < chunk {
+            // SPIN HERE
+        }
+        left -= chunk;
+    }
+}
+
+/* Microsecond Delay On A Free Running Timer */
+/* The Timer Must Count Up At 1 MHz With ARR = 0xFFFF (Or 0xFFFFFFFF On 32-Bit Timers), Any us Up To u32::MAX */
+impl common::DelayUs for Timer {
+    fn delay_us(&self, us: u32) {
+        spin_us(us, || self.get_cnt());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve_period, solve_pwm, spin_us, Align, Width};
+    use core::cell::Cell;
+
+    /* Least Error Of Any PSC + 1 x ARR + 1 Pair For num / den Clocks In 1 / den Clocks, Tries Both a Next To The Quotient */
+    fn brute_force(num: u128, den: u128, max: u128) -> u128 {
+        let mut best = u128::MAX;
+
+        for p in 1..=65_536u128 {
+            let a = num / (p * den);
+
+            for a in [a, a + 1] {
+                if (2..=max).contains(&a) {
+                    best = best.min((p * a * den).abs_diff(num));
+                }
+            }
+        }
+        return best;
+    }
+
+    /* Run The Delay On A Fake 16 Bit Counter That Advances By step On Every Read, Returns The Ticks Counted */
+    fn run(us: u32, step: u32) -> u64 {
+        let now = Cell::new(0xFFF0);
+        let reads = Cell::new(0u64);
+
+        spin_us(us, || {
+            let t = now.get();
+            now.set((t + step) & 0xFFFF);
+            reads.set(reads.get() + 1);
+            t
+        });
+        return reads.get() * step as u64;
+    }
+
+    #[test]
+    fn delay_long() {
+        for us in [0, 1, 65_535, 65_536, 100_000, 1_000_000] {
+            let chunks = (us as u64).div_ceil(32_768);
+            let ticks = run(us, 7);
+
+            assert!(ticks >= us as u64, "us {}", us);
+            assert!(ticks <= us as u64 + chunks * 2 * 7, "us {}", us);   // At most a start read and one late read per chunk
+        }
+    }
+
+    #[test]
+    fn solve_least_error() {
+        let mut period_ns: u64 = 7_777_777_777;
+
+        for _ in 0..40 {
+            let s = solve_period(period_ns, 80_000_000, Width::Bits16).ok().unwrap();
+            let counts = (s.get_psc() as u128 + 1) * (s.get_arr() as u128 + 1);
+            let err = (counts * 1_000_000_000).abs_diff(period_ns as u128 * 80_000_000);
+
+            // The solver rounds the period to 1/256 clock, allow that much on top of the best pair
+            assert!(err <= brute_force(period_ns as u128 * 80_000_000, 1_000_000_000, 65_536) + 1_000_000_000 / 128, "period {}", period_ns);
+
+            period_ns = (period_ns.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1) >> 31) % 50_000_000_000 + 1_000;
+        }
+
+        let s = solve_period(7_777_777_777, 80_000_000, Width::Bits16).ok().unwrap();
+        assert_eq!((s.get_psc(), s.get_arr()), (16_562, 37_566));      // 1.2 clocks off, the first 256 PSC were 37.8 off
+    }
+
+    #[test]
+    fn pwm_center_least_error() {
+        for freq_hz in [1u32, 7, 33, 66, 172, 279, 283, 1_234, 9_999] {
+            let s = solve_pwm(freq_hz, 80_000_000, Width::Bits16, Align::CenterBoth).ok().unwrap();
+            let half = 2 * freq_hz as u128;
+            let err = ((s.get_psc() as u128 + 1) * s.get_arr() as u128 * half).abs_diff(80_000_000);
+
+            assert!(s.get_arr() >= 2);
+            assert!(err <= brute_force(80_000_000, half, 65_535) + half / 128, "freq {}", freq_hz);
+        }
+    }
+
+    #[test]
+    fn delay_max() {
+        assert!(run(u32::MAX, 4_096) >= u32::MAX as u64);
     }
 }
@@ -1,7 +1,8 @@
 /* USART (Universal Synchronous and Asynchronous Receiver Transmitter) */
 /* Manual Page 1194 */
 
-use super::{common, pointer};
+use core::sync::atomic::{AtomicU32, Ordering};
+use super::{common, pointer, ring};
 
 // Any USART bidirectional communication requires a minimum of two pins: Receive data In (RX) and Transmit data Out (TX):
 // • RX: Receive data Input. This is the serial data input.
@@ -100,6 +101,13 @@ pub enum BaudRate {
     Baud921600  = 921600 
 }
 
+/* Interrupt Driven Buffers, Shared Between The USART Interrupt And The Main Code */
+pub struct Buffered<const R: usize, const T: usize> {
+    rx:         ring::Ring<R>,      // Received Bytes, Filled By The Interrupt
+    tx:         ring::Ring<T>,      // Bytes To Send, Drained By The Interrupt
+    overruns:   AtomicU32           // Bytes Lost, Hardware Overrun Or Receive Buffer Full
+}
+
 const SCLK_HZ:          u32 = 1000;
 const RTO_TIMEO_VALUE:  u32 = 10;
 
@@ -118,6 +126,8 @@ const RTO_TIMEO_MASK:   u32 = common::MASK_24_BIT;
 const UE_BIT:           u32 = common::BIT_0;
 const RE_BIT:           u32 = common::BIT_2;
 const TE_BIT:           u32 = common::BIT_3;
+const RXNEIE_BIT:       u32 = common::BIT_5;            /* Receive Not Empty Interrupt Enable */
+const TXEIE_BIT:        u32 = common::BIT_7;            /* Transmit Empty Interrupt Enable */
 const OVER_8_BIT:       u32 = common::BIT_15;
 /* CR2 */
 const RTOEN_BIT:        u32 = common::BIT_23;
@@ -286,6 +296,26 @@ impl Usart {
         pointer::clr_ptr_vol_bit_u32(self.cr1, TE_BIT);
     }
     
+    /* Enable The Receive Not Empty Interrupt */
+    pub fn set_rx_interrupt(&self) {
+        pointer::set_ptr_vol_bit_u32(self.cr1, RXNEIE_BIT);
+    }
+
+    /* Disable The Receive Not Empty Interrupt */
+    pub fn clr_rx_interrupt(&self) {
+        pointer::clr_ptr_vol_bit_u32(self.cr1, RXNEIE_BIT);
+    }
+
+    /* Enable The Transmit Empty Interrupt */
+    pub fn set_tx_interrupt(&self) {
+        pointer::set_ptr_vol_bit_u32(self.cr1, TXEIE_BIT);
+    }
+
+    /* Disable The Transmit Empty Interrupt */
+    pub fn clr_tx_interrupt(&self) {
+        pointer::clr_ptr_vol_bit_u32(self.cr1, TXEIE_BIT);
+    }
+
     /*
         USARTDIV is an unsigned fixed point number that is coded on the USART_BRR register.
         • When OVER8 = 0, BRR = USARTDIV.
@@ -304,3 +334,129 @@ impl Usart {
         };
     }
 }
+
+// Interrupt driven mode:
+// The interrupt moves every received byte from RDR into the receive ring and, while TXEIE is set, the next byte of the
+// transmit ring into TDR. TXEIE is cleared once the transmit ring is empty and set again by try_write.
+// Each ring has a single producer and a single consumer, so the main code never has to mask the interrupt.
+// An overrun (ORE) or a full receive ring loses bytes, both are counted. The blocking read and write must not be used at the same time.
+impl<const R: usize, const T: usize> Buffered<R, T> {
+    /* Initialize Empty Buffers, Each Holds One Byte Less Than Its Size */
+    pub const fn init() -> Buffered<R, T> {
+        return Buffered {
+            rx:         ring::Ring::init(),
+            tx:         ring::Ring::init(),
+            overruns:   AtomicU32::new(0)
+        };
+    }
+
+    /* Start Interrupt Driven Mode On An Opened USART, The USART Interrupt Must Be Enabled In The NVIC */
+    pub fn open(&self, usart: &Usart) {
+        pointer::set_ptr_vol_raw_u32(usart.icr, ORECR_BIT);
+        pointer::set_ptr_vol_bit_u32(usart.cr1, TE_BIT);
+        pointer::set_ptr_vol_bit_u32(usart.cr1, RE_BIT);
+        usart.set_rx_interrupt();
+
+        if !self.tx.is_empty() {
+            usart.set_tx_interrupt();
+        }
+    }
+
+    /* Stop Interrupt Driven Mode, Buffered Bytes Are Kept */
+    pub fn close(&self, usart: &Usart) {
+        usart.clr_rx_interrupt();
+        usart.clr_tx_interrupt();
+    }
+
+    /* Move Bytes Between The Rings And The USART, Call From The USART Interrupt */
+    pub fn on_interrupt(&self, usart: &Usart) {
+        if pointer::get_ptr_vol_bit_u32(usart.isr, ORE_BIT) {
+            pointer::set_ptr_vol_raw_u32(usart.icr, ORECR_BIT);
+            self.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+
+        while usart.get_read() {
+            if !self.rx.push(pointer::get_ptr_vol_raw_u8(usart.rdr)) {
+                self.overruns.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        if !pointer::get_ptr_vol_bit_u32(usart.cr1, TXEIE_BIT) || !pointer::get_ptr_vol_bit_u32(usart.isr, TXE_BIT) {
+            return;
+        }
+
+        match self.tx.pop() {
+            Some(byte)  =>  pointer::set_ptr_vol_raw_u8(usart.tdr, byte),
+            None        =>  {
+                usart.clr_tx_interrupt();
+
+                if !self.tx.is_empty() {
+                    usart.set_tx_interrupt();       // A byte was queued while the interrupt was being cleared
+                }
+            }
+        }
+    }
+
+    /* Take A Received Byte */
+    pub fn try_read(&self) -> Option<u8> {
+        return self.rx.pop();
+    }
+
+    /* Take Up To buf.len() Received Bytes, Returns The Number Taken */
+    pub fn read_buf(&self, buf: &mut [u8]) -> usize {
+        let mut i = 0;
+
+        while i < buf.len() {
+            match self.rx.pop() {
+                Some(byte)  =>  buf[i] = byte,
+                None        =>  break
+            }
+            i+=1;
+        }
+        return i;
+    }
+
+    /* Queue A Byte To Send, Returns False If The Transmit Buffer Is Full */
+    pub fn try_write(&self, usart: &Usart, byte: u8) -> bool {
+        if !self.tx.push(byte) {
+            return false;
+        }
+
+        usart.set_tx_interrupt();
+        return true;
+    }
+
+    /* Queue As Many Bytes As Fit, Returns The Number Queued */
+    pub fn write_buf(&self, usart: &Usart, buf: &[u8]) -> usize {
+        let mut i = 0;
+
+        while i < buf.len() && self.tx.push(buf[i]) {
+            i+=1;
+        }
+
+        if i > 0 {
+            usart.set_tx_interrupt();
+        }
+        return i;
+    }
+
+    /* Get The Number Of Received Bytes Waiting To Be Read */
+    pub fn available(&self) -> usize {
+        return self.rx.len();
+    }
+
+    /* Get The Number Of Bytes That Can Still Be Queued To Send */
+    pub fn tx_free(&self) -> usize {
+        return self.tx.free();
+    }
+
+    /* Get If Every Queued Byte Has Been Sent, Including The Last Frame */
+    pub fn get_tx_done(&self, usart: &Usart) -> bool {
+        return self.tx.is_empty() && pointer::get_ptr_vol_bit_u32(usart.isr, TC_BIT);
+    }
+
+    /* Get And Clear The Number Of Bytes Lost Since The Last Call */
+    pub fn get_overruns(&self) -> u32 {
+        return self.overruns.swap(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,253 @@
+/* WS2812 / SK6812 Addressable LED Driver (Timer PWM + DMA) */
+
+use super::{dma, timer};
+
+// Each bit is one 1.25us (800kHz) PWM period, the high time of the period sets the bit:
+// • 0: high 0.4us, low 0.85us         • 1: high 0.8us, low 0.45us
+// The pixel buffer is encoded into one compare value per bit, the DMA writes the next value into CCR on every update event
+// and the CCR preload applies it at the start of the next period. A run of 0 compare values (line held low) latches the data.
+// Colours are sent most significant bit first, green, red, blue (and white on the SK6812 RGBW).
+// Timer set up: count up with a period of 1.25us (ARR + 1 = timer clock / 800kHz) before the driver is initialized,
+// the DMA channel request must be the update event of the same timer.
+
+pub struct Ws2812<'a> {
+    timer:      &'a timer::Timer,   // Timer Generating The PWM
+    ch:         timer::Channel,     // PWM Channel Driving The Data Line
+    dma:        &'a dma::Dma,       // DMA Controller
+    dma_ch:     u32,                // DMA Channel Connected To The Timer Update Request
+    t0:         u16,                // Compare Value Of A 0 Bit
+    t1:         u16                 // Compare Value Of A 1 Bit
+}
+
+/* Pixel Colour */
+#[derive(Clone, Copy)]
+pub struct Pixel {
+    r:          u8,
+    g:          u8,
+    b:          u8,
+    w:          u8
+}
+
+/* Enumerations */
+/* Colour Order On The Wire */
+#[derive(Clone, Copy)]
+pub enum Order {
+    Grb,                        // WS2812 / SK6812 RGB
+    Grbw                        // SK6812 RGBW
+}
+
+/* Compare Values As A Fraction Of The Period, 0.4us / 1.25us And 0.8us / 1.25us */
+const T0_NUM:           u32 = 8;
+const T1_NUM:           u32 = 16;
+const T_DEN:            u32 = 25;
+
+const COLOUR_BITS:      usize = 8;
+pub const RESET_SLOTS:  usize = 240;                    /* 300us Low, Newer Parts Need More Than 280us To Latch */
+
+/* Gamma 2.8 Correction, Perceived Brightness To PWM Level */
+static GAMMA: [u8; 256] = [
+      0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,
+      0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   0,   1,   1,   1,   1,
+      1,   1,   1,   1,   1,   1,   1,   1,   1,   2,   2,   2,   2,   2,   2,   2,
+      2,   3,   3,   3,   3,   3,   3,   3,   4,   4,   4,   4,   4,   5,   5,   5,
+      5,   6,   6,   6,   6,   7,   7,   7,   7,   8,   8,   8,   9,   9,   9,  10,
+     10,  10,  11,  11,  11,  12,  12,  13,  13,  13,  14,  14,  15,  15,  16,  16,
+     17,  17,  18,  18,  19,  19,  20,  20,  21,  21,  22,  22,  23,  24,  24,  25,
+     25,  26,  27,  27,  28,  29,  29,  30,  31,  32,  32,  33,  34,  35,  35,  36,
+     37,  38,  39,  39,  40,  41,  42,  43,  44,  45,  46,  47,  48,  49,  50,  50,
+     51,  52,  54,  55,  56,  57,  58,  59,  60,  61,  62,  63,  64,  66,  67,  68,
+     69,  70,  72,  73,  74,  75,  77,  78,  79,  81,  82,  83,  85,  86,  87,  89,
+     90,  92,  93,  95,  96,  98,  99, 101, 102, 104, 105, 107, 109, 110, 112, 114,
+    115, 117, 119, 120, 122, 124, 126, 127, 129, 131, 133, 135, 137, 138, 140, 142,
+    144, 146, 148, 150, 152, 154, 156, 158, 160, 162, 164, 167, 169, 171, 173, 175,
+    177, 180, 182, 184, 186, 189, 191, 193, 196, 198, 200, 203, 205, 208, 210, 213,
+    215, 218, 220, 223, 225, 228, 231, 233, 236, 239, 241, 244, 247, 249, 252, 255,
+];
+
+pub fn gamma(val: u8) -> u8 {
+    return GAMMA[val as usize];
+}
+
+/* Compare Values Of A 0 Bit And A 1 Bit For A Timer Period Of ARR + 1 Counts, Saturating At 0xFFFF */
+pub fn compare(arr: u32) -> (u16, u16) {
+    let period = arr as u64 + 1;
+    let scale = |num: u32| ((period * num as u64) / T_DEN as u64).min(u16::MAX as u64) as u16;
+
+    return (scale(T0_NUM), scale(T1_NUM));
+}
+
+/* Number Of Compare Values Needed For The Pixels Including The Latch */
+pub fn buf_len(pixels: usize, order: Order) -> usize {
+    return (pixels * colours(order) * COLOUR_BITS) + RESET_SLOTS;
+}
+
+/* Encode The Pixels Into Compare Values, Returns The Number Of Values Written Or 0 If The Buffer Is Too Small */
+pub fn encode(pixels: &[Pixel], order: Order, gamma_correct: bool, t0: u16, t1: u16, buf: &mut [u16]) -> usize {
+    let len = buf_len(pixels.len(), order);
+    let mut i = 0;
+
+    if buf.len() < len {
+        return 0;
+    }
+
+    for p in pixels.iter() {
+        let colour = [p.g, p.r, p.b, p.w];
+
+        for c in colour[..colours(order)].iter() {
+            let val = if gamma_correct { gamma(*c) } else { *c };
+
+            for bit in (0..COLOUR_BITS).rev() {
+                buf[i] = if (val >> bit) & 0x01 != 0 { t1 } else { t0 };
+                i+=1;
+            }
+        }
+    }
+
+    for slot in buf[i..len].iter_mut() {
+        *slot = 0;
+    }
+    return len;
+}
+
+fn colours(order: Order) -> usize {
+    return match order {
+        Order::Grb  => 3,
+        Order::Grbw => 4
+    };
+}
+
+impl<'a> Ws2812<'a> {
+    /* Initialize The Structure And Set Up The PWM Channel And DMA Channel, The Timer Period Must Already Be Set */
+    pub fn init(timer: &'a timer::Timer, ch: timer::Channel, dma: &'a dma::Dma, dma_ch: u32, dma_request: u32) -> Ws2812<'a> {
+        let (t0, t1) = compare(timer.get_arr());
+
+        timer.stop();
+        timer.set_pwm(ch);
+        timer.set_preload(ch);
+        timer.set_ccr(ch, 0);
+        dma.open(dma_ch, dma_request, dma::Direction::MemToPeriph, dma::Size::Bits32, dma::Size::Bits16, dma::Priority::High);
+
+        return Ws2812 {
+            timer,
+            ch,
+            dma,
+            dma_ch,
+            t0,
+            t1
+        };
+    }
+
+    /* Encode The Pixels With The Compare Values Of This Timer */
+    pub fn encode(&self, pixels: &[Pixel], order: Order, gamma_correct: bool, buf: &mut [u16]) -> usize {
+        return encode(pixels, order, gamma_correct, self.t0, self.t1, buf);
+    }
+
+    /* Stream The Encoded Buffer To The Strip, The Buffer Must Not Change Until The Transfer Is Done */
+    pub fn show(&self, buf: &[u16]) {
+        self.timer.stop();
+        self.timer.clr_cnt();
+        self.dma.start(self.dma_ch, self.timer.get_ccr_addr(self.ch), buf.as_ptr() as u32, buf.len() as u32);
+        self.timer.set_dma_update();
+        self.timer.start();
+    }
+
+    /* Get If The Transfer Is Still Running */
+    pub fn busy(&self) -> bool {
+        return !self.dma.get_tc(self.dma_ch) && self.dma.get_cnt(self.dma_ch) > 0;
+    }
+
+    /* Stop The Transfer And Hold The Line Low */
+    pub fn stop(&self) {
+        self.timer.clr_dma_update();
+        self.dma.stop(self.dma_ch);
+        self.timer.stop();
+        self.timer.set_ccr(self.ch, 0);
+    }
+}
+
+impl Pixel {
+    pub fn rgb(r: u8, g: u8, b: u8) -> Pixel {
+        return Pixel { r, g, b, w: 0 };
+    }
+
+    pub fn rgbw(r: u8, g: u8, b: u8, w: u8) -> Pixel {
+        return Pixel { r, g, b, w };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARR:      u32 = 99;                   // 80MHz / 800kHz - 1
+    const T0:       u16 = 32;                   // 100 x 8 / 25
+    const T1:       u16 = 64;                   // 100 x 16 / 25
+
+    /* Decode One Colour Byte Back From Its Compare Values */
+    fn byte(buf: &[u16]) -> u8 {
+        let mut val = 0;
+
+        for slot in buf[..COLOUR_BITS].iter() {
+            assert!(*slot == T0 || *slot == T1);
+            val = (val << 1) | (*slot == T1) as u8;
+        }
+        return val;
+    }
+
+    #[test]
+    fn compare_values() {
+        assert_eq!(compare(ARR), (T0, T1));
+        assert_eq!(compare(149), (48, 96));     // 120MHz
+        assert_eq!(compare(0x1_FFFF), (41_943, u16::MAX));
+        assert_eq!(compare(u32::MAX), (u16::MAX, u16::MAX));
+    }
+
+    #[test]
+    fn grb_order_msb_first() {
+        let mut buf = [0xAAAA; 24 + RESET_SLOTS];
+
+        assert_eq!(encode(&[Pixel::rgb(0x12, 0x80, 0x01)], Order::Grb, false, T0, T1, &mut buf), 24 + RESET_SLOTS);
+        assert_eq!(&buf[..8], &[T1, T0, T0, T0, T0, T0, T0, T0]);
+        assert_eq!(byte(&buf[0..]), 0x80);
+        assert_eq!(byte(&buf[8..]), 0x12);
+        assert_eq!(byte(&buf[16..]), 0x01);
+    }
+
+    #[test]
+    fn grbw_order() {
+        let mut buf = [0; 32 + RESET_SLOTS];
+
+        assert_eq!(encode(&[Pixel::rgbw(1, 2, 3, 4)], Order::Grbw, false, T0, T1, &mut buf), 32 + RESET_SLOTS);
+        assert_eq!([byte(&buf[0..]), byte(&buf[8..]), byte(&buf[16..]), byte(&buf[24..])], [2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn gamma_applied() {
+        let mut buf = [0; 24 + RESET_SLOTS];
+
+        assert_eq!(gamma(0), 0);
+        assert_eq!(gamma(128), 37);
+        assert_eq!(gamma(255), 255);
+
+        encode(&[Pixel::rgb(128, 255, 27)], Order::Grb, true, T0, T1, &mut buf);
+        assert_eq!([byte(&buf[0..]), byte(&buf[8..]), byte(&buf[16..])], [255, 37, 0]);
+    }
+
+    #[test]
+    fn reset_padding() {
+        let mut buf = [0xAAAA; 48 + RESET_SLOTS + 4];
+        let len = encode(&[Pixel::rgb(255, 255, 255); 2], Order::Grb, false, T0, T1, &mut buf);
+
+        assert_eq!(len, buf_len(2, Order::Grb));
+        assert!(buf[..48].iter().all(|s| *s == T1));
+        assert!(buf[48..len].iter().all(|s| *s == 0));
+        assert!(buf[len..].iter().all(|s| *s == 0xAAAA));                       // Past the length is left alone
+    }
+
+    #[test]
+    fn buffer_too_small() {
+        let mut buf = [0; 24 + RESET_SLOTS - 1];
+
+        assert_eq!(encode(&[Pixel::rgb(1, 2, 3)], Order::Grb, false, T0, T1, &mut buf), 0);
+    }
+}